@@ -0,0 +1,357 @@
+use std::fmt;
+
+use iced::Point;
+
+use crate::layer::Edges;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl Align {
+    pub const ALL: [Align; 6] = [
+        Align::Left,
+        Align::Center,
+        Align::Right,
+        Align::Top,
+        Align::Middle,
+        Align::Bottom,
+    ];
+}
+
+impl fmt::Display for Align {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Align::Left => "Left",
+            Align::Center => "Center",
+            Align::Right => "Right",
+            Align::Top => "Top",
+            Align::Middle => "Middle",
+            Align::Bottom => "Bottom",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignTarget {
+    Canvas,
+    Selection,
+}
+
+impl AlignTarget {
+    pub const ALL: [AlignTarget; 2] = [AlignTarget::Canvas, AlignTarget::Selection];
+}
+
+impl fmt::Display for AlignTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AlignTarget::Canvas => "To canvas",
+            AlignTarget::Selection => "To selection",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Returns the edges enclosing all of the given ones.
+pub fn bounds_of(edges: &[Edges]) -> Option<Edges> {
+    let first = edges.first()?;
+    let (left, right, top, bottom) = edges.iter().fold(
+        (first.left, first.right, first.top, first.bottom),
+        |(left, right, top, bottom), edges| {
+            (
+                left.min(edges.left),
+                right.max(edges.right),
+                top.min(edges.top),
+                bottom.max(edges.bottom),
+            )
+        },
+    );
+
+    Some(Edges {
+        left,
+        right,
+        top,
+        bottom,
+        center_x: (left + right) * 0.5,
+        center_y: (top + bottom) * 0.5,
+    })
+}
+
+/// Lines every layer up with the matching edge or center of `reference`.
+pub fn align(edges: &[Edges], align: Align, reference: &Edges) -> Vec<Point> {
+    edges
+        .iter()
+        .map(|edges| match align {
+            Align::Left => Point::new(reference.left - edges.left, 0.),
+            Align::Center => Point::new(reference.center_x - edges.center_x, 0.),
+            Align::Right => Point::new(reference.right - edges.right, 0.),
+            Align::Top => Point::new(0., reference.top - edges.top),
+            Align::Middle => Point::new(0., reference.center_y - edges.center_y),
+            Align::Bottom => Point::new(0., reference.bottom - edges.bottom),
+        })
+        .collect()
+}
+
+/// Spaces layers evenly between the outermost two, which stay in place.
+pub fn distribute(edges: &[Edges], axis: Axis) -> Vec<Point> {
+    let mut deltas = vec![Point::ORIGIN; edges.len()];
+    if edges.len() < 3 {
+        return deltas;
+    }
+
+    let start = |edges: &Edges| match axis {
+        Axis::Horizontal => edges.left,
+        Axis::Vertical => edges.top,
+    };
+    let size = |edges: &Edges| match axis {
+        Axis::Horizontal => edges.width(),
+        Axis::Vertical => edges.height(),
+    };
+
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by(|a, b| start(&edges[*a]).total_cmp(&start(&edges[*b])));
+
+    let first = &edges[order[0]];
+    let end = order
+        .iter()
+        .map(|index| start(&edges[*index]) + size(&edges[*index]))
+        .fold(f32::NEG_INFINITY, f32::max);
+    let occupied: f32 = order.iter().map(|index| size(&edges[*index])).sum();
+    let gap = (end - start(first) - occupied) / (edges.len() - 1) as f32;
+
+    let mut position = start(first);
+    for index in order {
+        let offset = position - start(&edges[index]);
+        deltas[index] = match axis {
+            Axis::Horizontal => Point::new(offset, 0.),
+            Axis::Vertical => Point::new(0., offset),
+        };
+        position += size(&edges[index]) + gap;
+    }
+
+    deltas
+}
+
+/// Moves layers apart so none overlap and the gaps between neighbours are equal.
+///
+/// Layers are grouped into rows by their vertical position and keep their left-to-right
+/// order inside a row. Returns the delta to move every layer by, in input order.
+pub fn tidy(edges: &[Edges], default_gap: f32) -> Vec<Point> {
+    let mut deltas = vec![Point::ORIGIN; edges.len()];
+    if edges.is_empty() {
+        return deltas;
+    }
+
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by(|a, b| edges[*a].top.total_cmp(&edges[*b].top));
+
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    let mut row_bottom = f32::NEG_INFINITY;
+    for index in order {
+        let layer = &edges[index];
+        match rows.last_mut() {
+            Some(row) if layer.center_y < row_bottom => {
+                row.push(index);
+                row_bottom = row_bottom.max(layer.bottom);
+            }
+            _ => {
+                rows.push(vec![index]);
+                row_bottom = layer.bottom;
+            }
+        }
+    }
+
+    for row in &mut rows {
+        row.sort_by(|a, b| edges[*a].left.total_cmp(&edges[*b].left));
+    }
+
+    let row_spans: Vec<(f32, f32)> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |span, index| {
+                    (
+                        span.0.min(edges[*index].top),
+                        span.1.max(edges[*index].bottom),
+                    )
+                })
+        })
+        .collect();
+
+    let gap_x = average_gap(
+        rows.iter()
+            .flat_map(|row| row.windows(2))
+            .map(|pair| edges[pair[1]].left - edges[pair[0]].right),
+        default_gap,
+    );
+    let gap_y = average_gap(
+        row_spans.windows(2).map(|pair| pair[1].0 - pair[0].1),
+        default_gap,
+    );
+
+    for row in &rows {
+        let total_width = row.iter().map(|index| edges[*index].width()).sum::<f32>()
+            + gap_x * (row.len() - 1) as f32;
+        let left = edges[row[0]].left;
+        let right = row
+            .iter()
+            .map(|index| edges[*index].right)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        let mut x = (left + right - total_width) * 0.5;
+        for index in row {
+            deltas[*index].x = x - edges[*index].left;
+            x += edges[*index].width() + gap_x;
+        }
+    }
+
+    let total_height =
+        row_spans.iter().map(|span| span.1 - span.0).sum::<f32>() + gap_y * (rows.len() - 1) as f32;
+    let top = row_spans[0].0;
+    let bottom = row_spans
+        .iter()
+        .map(|span| span.1)
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    let mut y = (top + bottom - total_height) * 0.5;
+    for (row, span) in rows.iter().zip(&row_spans) {
+        for index in row {
+            deltas[*index].y = y - span.0;
+        }
+        y += span.1 - span.0 + gap_y;
+    }
+
+    deltas
+}
+
+/// Averages the gaps that are already open, overlaps don't count towards the spacing.
+fn average_gap(gaps: impl Iterator<Item = f32>, default_gap: f32) -> f32 {
+    let (sum, count) = gaps
+        .filter(|gap| *gap > 0.)
+        .fold((0., 0), |(sum, count), gap| (sum + gap, count + 1));
+
+    if count > 0 {
+        sum / count as f32
+    } else {
+        default_gap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::Rectangle;
+
+    use super::*;
+
+    fn edges(x: f32, y: f32, width: f32, height: f32) -> Edges {
+        Edges::of(&Rectangle {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    fn moved(edges: &Edges, delta: Point) -> Edges {
+        self::edges(
+            edges.left + delta.x,
+            edges.top + delta.y,
+            edges.width(),
+            edges.height(),
+        )
+    }
+
+    #[test]
+    fn bounds_enclose_every_layer() {
+        let bounds = bounds_of(&[edges(10., 20., 30., 40.), edges(-5., 50., 10., 10.)]).unwrap();
+
+        assert_eq!(
+            (bounds.left, bounds.top, bounds.right, bounds.bottom),
+            (-5., 20., 40., 60.)
+        );
+        assert_eq!((bounds.center_x, bounds.center_y), (17.5, 40.));
+        assert!(bounds_of(&[]).is_none());
+    }
+
+    #[test]
+    fn align_moves_along_one_axis_only() {
+        let layers = [edges(10., 0., 20., 20.), edges(40., 5., 10., 10.)];
+        let reference = bounds_of(&layers).unwrap();
+
+        assert_eq!(
+            align(&layers, Align::Left, &reference),
+            [Point::new(0., 0.), Point::new(-30., 0.)]
+        );
+        assert_eq!(
+            align(&layers, Align::Bottom, &reference),
+            [Point::new(0., 0.), Point::new(0., 5.)]
+        );
+        assert_eq!(
+            align(&layers, Align::Center, &reference),
+            [Point::new(10., 0.), Point::new(-15., 0.)]
+        );
+    }
+
+    #[test]
+    fn distribute_evens_out_the_gaps_between_the_outermost_layers() {
+        let layers = [
+            edges(100., 0., 20., 10.),
+            edges(0., 0., 10., 10.),
+            edges(15., 0., 10., 10.),
+        ];
+
+        assert_eq!(
+            distribute(&layers, Axis::Horizontal),
+            [Point::new(0., 0.), Point::new(0., 0.), Point::new(35., 0.)]
+        );
+        assert_eq!(
+            distribute(&layers[..2], Axis::Horizontal),
+            [Point::ORIGIN, Point::ORIGIN]
+        );
+    }
+
+    #[test]
+    fn tidy_separates_overlapping_layers() {
+        let layers = [edges(0., 0., 40., 40.), edges(20., 0., 40., 40.)];
+        let deltas = tidy(&layers, 10.);
+        assert_eq!(deltas, [Point::new(-15., 0.), Point::new(15., 0.)]);
+
+        let tidied: Vec<Edges> = layers
+            .iter()
+            .zip(&deltas)
+            .map(|(layer, delta)| moved(layer, *delta))
+            .collect();
+        assert_eq!(tidied[1].left - tidied[0].right, 10.);
+    }
+
+    #[test]
+    fn tidy_keeps_rows_apart() {
+        let layers = [
+            edges(0., 0., 40., 40.),
+            edges(50., 5., 40., 40.),
+            edges(0., 30., 40., 40.),
+        ];
+        let tidied: Vec<Edges> = layers
+            .iter()
+            .zip(tidy(&layers, 10.))
+            .map(|(layer, delta)| moved(layer, delta))
+            .collect();
+
+        let first_row_bottom = tidied[0].bottom.max(tidied[1].bottom);
+        assert!(tidied[2].top >= first_row_bottom);
+        assert_eq!(tidied[1].left - tidied[0].right, 10.);
+    }
+}
@@ -1,57 +1,357 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    sync::Arc,
+};
 
+use arrange::{Align, AlignTarget, Axis};
 use iced::Length::Fill;
+use iced::futures::SinkExt;
 use iced::widget::container::Style;
-use iced::widget::{button, column, container, row, text};
-use iced::{Alignment, Length, Point, keyboard};
+use iced::widget::image::Handle;
+use iced::widget::text_input::Id as InputId;
+use iced::widget::{
+    button, center, column, container, mouse_area, opaque, pick_list, row, scrollable, slider,
+    stack, text, text_input, toggler,
+};
+use iced::{Alignment, Length, Padding, Point, Rectangle, Size, clipboard, event, keyboard, mouse};
 use iced::{Element, Subscription, Task};
 use id::Id;
-use maker_canvas::MakerCanvas;
+use import::{ImportOptions, MinResolution, Orientation, SortOrder};
+use keymap::{Action, Chord, Keymap};
+use layout::{LayoutKind, LayoutOptions, Weighting};
+use library::Library;
+use maker_canvas::{ExportPixels, MakerCanvas};
 use rfd::AsyncFileDialog;
 use simulator::Simulator;
 
-use crate::{id, maker_canvas, simulator, styles, utils};
+use crate::{
+    arrange,
+    decode::{self, Preview},
+    error::Error,
+    id, import, keymap,
+    layer::Layer,
+    layout, library, maker_canvas, palette, project,
+    rng::Rng,
+    simulator, styles, thumbnails, utils, watcher,
+};
 
-#[derive(Clone, Debug)]
-pub struct PngError(pub String);
+/// Side of the window the asset browser is docked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dock {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl Dock {
+    const ALL: [Dock; 3] = [Dock::Left, Dock::Right, Dock::Bottom];
+}
+
+impl fmt::Display for Dock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Dock::Left => "Dock left",
+            Dock::Right => "Dock right",
+            Dock::Bottom => "Dock bottom",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Entry of the asset browser's tag filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagFilter {
+    All,
+    Tag(String),
+}
+
+impl fmt::Display for TagFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagFilter::All => write!(f, "All tags"),
+            TagFilter::Tag(tag) => write!(f, "{tag}"),
+        }
+    }
+}
+
+/// Non-fatal problem shown in the corner until it is dismissed.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub title: String,
+    pub details: Vec<String>,
+}
+
+/// Numeric field of the properties panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    X,
+    Y,
+    Width,
+    Height,
+    Rotation,
+}
+
+/// Text typed into a properties panel field and the layer's rect and rotation right after
+/// it, the draft is dropped once the layer is changed any other way.
+struct PropertyDraft {
+    id: Id,
+    property: Property,
+    text: String,
+    rect: Rectangle,
+    rotation: Option<f32>,
+}
+
+/// Pixels read from the system clipboard.
+#[derive(Clone)]
+pub struct ClipboardImage(pub Arc<image::RgbaImage>);
+
+impl std::fmt::Debug for ClipboardImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ClipboardImage({}x{})", self.0.width(), self.0.height())
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum Message {
     None,
     AddImage,
     ImageSelected(Option<Vec<PathBuf>>),
+    ImageDecoded(Id, u64, Result<Preview, Error>),
     RemoveImage(Id),
     SaveAsPng,
     SaveApply,
     SelectLayer(usize),
+    ToggleLayerSelection(usize),
+    LayerRowPressed(usize),
+    LayerRowEntered(usize),
+    LayerDragEnded,
+    LayerDragCancelled,
+    BringToFront,
+    SendToBack,
+    SelectAll,
+    SelectInRect(Rectangle, bool),
     DeselectLayers,
     StartDrag,
     MoveSelection(Point, bool),
     ResizeSelection(Point, Point, bool),
     SavePathSelected(Option<PathBuf>),
     SaveApplyPathSelected(Option<PathBuf>),
+    SaveDecoded(PathBuf, Result<ExportPixels, Error>),
+    SaveApplyDecoded(PathBuf, Result<ExportPixels, Error>),
+    WallpaperSet(Result<(), Error>),
+    DismissNotification(usize),
     ShiftHeld(bool),
+    CtrlHeld(bool),
+    GroupSelection,
+    UngroupSelection,
+    Ungroup(Id),
+    RenameLayer(Id, String),
+    ToggleLayerExpanded(Id),
+    ToggleLayerHidden(Id),
+    ToggleLayerLocked(Id),
+    LayerOpacityChanged(Id, f32),
+    LayerFrameChanged(Id, usize),
+    NudgeSelection(Point),
+    DeleteSelection,
+    DuplicateSelection,
+    CopySelection,
+    CutSelection,
+    Paste,
+    ClipboardRead(Option<String>),
+    ClipboardImageRead(Option<ClipboardImage>),
+    ToggleAssetBrowser,
+    AssetDockSelected(Dock),
+    AddLibraryFolder,
+    LibraryFolderSelected(Option<PathBuf>),
+    RemoveLibraryFolder(PathBuf),
+    AssetsScanned(Vec<PathBuf>),
+    /// Thumbnail of an asset, and why it couldn't be cached.
+    AssetThumbnailLoaded(PathBuf, Option<Handle>, Option<Error>),
+    AssetQueryChanged(String),
+    AssetFavoritesOnlyToggled(bool),
+    AssetTagFilterSelected(TagFilter),
+    AssetPressed(PathBuf),
+    AssetDragEnded,
+    ToggleAssetFavorite(PathBuf),
+    AssetTagDraftChanged(String),
+    AddAssetTag,
+    RemoveAssetTag(PathBuf, String),
+    OpenImportDialog,
+    CloseImportDialog,
+    ImportRecursiveToggled(bool),
+    ImportMinResolutionSelected(MinResolution),
+    ImportOrientationSelected(Orientation),
+    ImportModifiedAfterChanged(String),
+    ImportSortSelected(SortOrder),
+    ImportLimitChanged(String),
+    ImportRandomToggled(bool),
+    ImportFolder,
+    ImportFolderSelected(Option<PathBuf>),
+    FolderScanned(Vec<PathBuf>, Vec<Error>),
+    SourceChanged(PathBuf),
+    OpenRelinkDialog,
+    CloseRelinkDialog,
+    SearchMissingFolder,
+    MissingFolderSelected(Option<PathBuf>),
+    RelinkCandidatesFound(Vec<PathBuf>),
+    SaveProject,
+    SaveProjectPathSelected(Option<PathBuf>),
+    LoadProject,
+    LoadProjectPathSelected(Option<PathBuf>),
+    CycleSelection(bool),
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
+    PaletteKeyPressed(keyboard::Key, keyboard::Modifiers),
+    ToggleShortcutsOverlay,
+    OpenShortcutSettings,
+    CloseShortcutSettings,
+    StartRebind(Action),
+    ConfirmRebind,
+    CancelRebind,
+    RemoveChord(Action, Chord),
+    ResetShortcuts,
+    TogglePalette,
+    PaletteQueryChanged(String),
+    PaletteSubmitted,
+    PaletteCommand(Box<Message>),
+    CanvasSizeSelected(Size),
+    CursorMoved(Point),
+    /// Path dropped onto the canvas, where, and its index among the files dropped together.
+    FileDropped(PathBuf, Option<Point>, usize),
+    DroppedImagesCollected(Vec<PathBuf>, Option<Point>),
+    OpenContextMenu(Id),
+    CloseContextMenu,
+    ContextMenuCommand(Box<Message>),
+    FlipSelection(Axis),
+    ResetSelectionSize,
+    ReplaceImage(Id),
+    ReplacementSelected(Id, Option<PathBuf>),
+    RevealSource(Id),
+    PropertyEdited(Id, Property, String),
+    PropertySubmitted,
+    AspectLockToggled(bool),
     Undo,
     Redo,
     LeftButtonReleased,
     LayerUp(Id),
     LayerDown(Id),
+    AutoLayout,
+    LayoutKindSelected(LayoutKind),
+    LayoutGutterChanged(f32),
+    LayoutMarginChanged(f32),
+    LayoutCornerRadiusChanged(f32),
+    LayoutCropToggled(bool),
+    LayoutWeightingSelected(Weighting),
+    ShuffleLayout,
+    LayerImportanceChanged(Id, f32),
+    ScatterMinScaleChanged(f32),
+    ScatterMaxScaleChanged(f32),
+    ScatterRotationChanged(f32),
+    ScatterPolaroidToggled(bool),
+    ScatterShadowToggled(bool),
+    TidyUp,
+    AlignSelection(Align),
+    AlignTargetSelected(AlignTarget),
+    DistributeSelection(Axis),
 }
 
 pub struct BgMaker {
     canvas: MakerCanvas,
     simulator: Simulator,
+    layout_options: LayoutOptions,
+    align_target: AlignTarget,
+    /// Row being dragged in the layer panel and the row it would be dropped on.
+    dragged_row: Option<usize>,
+    drop_row: Option<usize>,
+    /// Text of the property field being typed in, kept until it is submitted so partial
+    /// numbers like "-" or "12." can be entered.
+    property_draft: Option<PropertyDraft>,
+    aspect_locked: bool,
+    keymap: Keymap,
+    shortcuts_overlay_open: bool,
+    shortcut_settings_open: bool,
+    /// Action waiting for the next key press to become its new binding.
+    recording_action: Option<Action>,
+    /// Rebinding that would take a chord away from the listed actions, waiting for the
+    /// user to confirm it.
+    pending_rebind: Option<(Action, Chord, Vec<Action>)>,
+    /// Search text of the command palette, `None` while it is closed.
+    palette_query: Option<String>,
+    palette_selected: usize,
+    /// Last known cursor position in the window, where context menus open.
+    cursor_position: Point,
+    /// Images added by the current drop so far, each one is offset a little further.
+    dropped_images: usize,
+    /// Layer the context menu was opened on and where it is shown.
+    context_menu: Option<(Id, Point)>,
+    import_dialog_open: bool,
+    import_options: ImportOptions,
+    /// Texts of the import fields that are parsed into `import_options`.
+    import_modified_after: String,
+    import_limit: String,
+    relink_dialog_open: bool,
+    notifications: Vec<Notification>,
+    library: Library,
+    browser_open: bool,
+    browser_dock: Dock,
+    browser_filter: library::Filter,
+    /// Images found in the library folders, and their thumbnails once loaded.
+    assets: Vec<PathBuf>,
+    asset_thumbnails: HashMap<PathBuf, Handle>,
+    /// Assets whose thumbnails are being loaded, so rescans don't queue them again.
+    loading_thumbnails: HashSet<PathBuf>,
+    /// Asset whose name and tags are shown below the grid.
+    selected_asset: Option<PathBuf>,
+    asset_tag_draft: String,
 }
 
 impl BgMaker {
     pub fn new() -> (Self, Task<Message>) {
-        (
-            Self {
-                canvas: MakerCanvas::new(1280., 720.),
-                simulator: Simulator::new(),
-            },
-            Task::none(),
-        )
+        let (keymap, keymap_error) = Keymap::load();
+        let (library, library_error) = Library::load();
+
+        let mut bg_maker = Self {
+            canvas: MakerCanvas::new(1280., 720.),
+            simulator: Simulator::new(),
+            layout_options: LayoutOptions::default(),
+            align_target: AlignTarget::Canvas,
+            dragged_row: None,
+            drop_row: None,
+            property_draft: None,
+            aspect_locked: false,
+            keymap,
+            shortcuts_overlay_open: false,
+            shortcut_settings_open: false,
+            recording_action: None,
+            pending_rebind: None,
+            palette_query: None,
+            palette_selected: 0,
+            cursor_position: Point::ORIGIN,
+            dropped_images: 0,
+            context_menu: None,
+            import_dialog_open: false,
+            import_options: ImportOptions::default(),
+            import_modified_after: String::new(),
+            import_limit: String::new(),
+            relink_dialog_open: false,
+            notifications: Vec::new(),
+            library,
+            browser_open: false,
+            browser_dock: Dock::Left,
+            browser_filter: library::Filter::default(),
+            assets: Vec::new(),
+            asset_thumbnails: HashMap::new(),
+            loading_thumbnails: HashSet::new(),
+            selected_asset: None,
+            asset_tag_draft: String::new(),
+        };
+        bg_maker.notify_errors(
+            "Settings not loaded",
+            keymap_error.into_iter().chain(library_error).collect(),
+        );
+
+        (bg_maker, Task::none())
     }
 
     pub fn title(&self) -> String {
@@ -63,7 +363,7 @@ impl BgMaker {
             Message::AddImage => {
                 let task = async {
                     let files = AsyncFileDialog::new()
-                        .add_filter("image", &["png", "jpg", "jpeg"])
+                        .add_filter("image", &import::IMAGE_EXTENSIONS)
                         .pick_files()
                         .await;
                     files.map(|selected| {
@@ -79,12 +379,80 @@ impl BgMaker {
                 for path in paths {
                     self.canvas.add_image_layer(path);
                 }
+                return self.load_pending_images();
+            }
+            Message::ImageDecoded(id, generation, Ok(mut preview)) => {
+                let cache_error = preview.cache_error.take();
+                self.canvas.finish_loading(id, generation, preview);
+                self.notify_errors("Thumbnails not cached", cache_error.into_iter().collect());
+                return self.load_pending_images();
+            }
+            Message::ImageDecoded(id, generation, Err(error)) => {
+                if !self.canvas.fail_loading(id, generation) {
+                    return Task::none();
+                }
+                let missing = self
+                    .canvas
+                    .find_layer(id)
+                    .is_some_and(|layer| layer.handler.is_missing());
+                let title = if missing {
+                    "Some images are missing"
+                } else {
+                    "Some images couldn't be loaded"
+                };
+                self.notify_errors(title, vec![error]);
+                return self.load_pending_images();
             }
             Message::RemoveImage(id) => {
                 self.canvas.remove_layer(id);
             }
             Message::SelectLayer(index) => {
                 self.canvas.select_layer(index);
+                self.property_draft = None;
+            }
+            Message::ToggleLayerSelection(index) => {
+                self.canvas.toggle_layer_selection(index);
+                self.property_draft = None;
+            }
+            Message::LayerRowPressed(index) => {
+                self.property_draft = None;
+                if self.canvas.toggle_modifier_held() {
+                    self.canvas.toggle_layer_selection(index);
+                } else {
+                    self.canvas.select_layer(index);
+                }
+                self.dragged_row = Some(index);
+                self.drop_row = Some(index);
+            }
+            Message::LayerRowEntered(index) => {
+                if self.dragged_row.is_some() {
+                    self.drop_row = Some(index);
+                }
+            }
+            Message::LayerDragEnded => {
+                if let (Some(from), Some(to)) = (self.dragged_row.take(), self.drop_row.take()) {
+                    if from != to {
+                        self.canvas.move_layer_to(from, to);
+                    }
+                }
+            }
+            Message::LayerDragCancelled => {
+                self.dragged_row = None;
+                self.drop_row = None;
+            }
+            Message::BringToFront => {
+                self.canvas.bring_selection_to_front();
+            }
+            Message::SendToBack => {
+                self.canvas.send_selection_to_back();
+            }
+            Message::SelectAll => {
+                self.canvas.select_all();
+                self.property_draft = None;
+            }
+            Message::SelectInRect(rect, additive) => {
+                self.canvas.select_in_rect(rect, additive);
+                self.property_draft = None;
             }
             Message::StartDrag => {
                 self.canvas.on_start_drag();
@@ -94,9 +462,11 @@ impl BgMaker {
             }
             Message::ResizeSelection(delta, point, preserve_aspect) => {
                 self.canvas.resize_selection(delta, point, preserve_aspect);
+                return self.load_pending_images();
             }
             Message::DeselectLayers => {
                 self.canvas.deselect_layers();
+                self.property_draft = None;
             }
             Message::SaveAsPng => {
                 let task = choose_save_file_path();
@@ -107,10 +477,27 @@ impl BgMaker {
                 return Task::perform(task, Message::SaveApplyPathSelected);
             }
             Message::SavePathSelected(Some(path)) => {
-                self.canvas.export_as_png(&mut self.simulator, &path);
+                return self.decode_for_export(path, Message::SaveDecoded);
             }
             Message::SaveApplyPathSelected(Some(path)) => {
-                self.canvas.export_as_png(&mut self.simulator, &path);
+                return self.decode_for_export(path, Message::SaveApplyDecoded);
+            }
+            Message::SaveDecoded(path, pixels) => {
+                if let Err(error) = pixels.and_then(|pixels| {
+                    self.canvas
+                        .export_as_png(&mut self.simulator, &path, &pixels)
+                }) {
+                    self.notify_errors("Export failed", vec![error]);
+                }
+            }
+            Message::SaveApplyDecoded(path, pixels) => {
+                if let Err(error) = pixels.and_then(|pixels| {
+                    self.canvas
+                        .export_as_png(&mut self.simulator, &path, &pixels)
+                }) {
+                    self.notify_errors("Export failed", vec![error]);
+                    return Task::none();
+                }
 
                 let task = async move {
                     for _ in 0..10 {
@@ -120,29 +507,653 @@ impl BgMaker {
                         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
                     }
 
-                    let path_str = path.to_str();
-                    match utils::wallpaper::set_wallpaper(path_str.unwrap()) {
-                        Ok(_) => println!("Wallpaper set successfully!"),
-                        Err(e) => eprintln!("Failed to set wallpaper: {:?}", e),
-                    }
+                    utils::wallpaper::set_wallpaper(&path)
                 };
 
-                return Task::perform(task, |_| Message::None);
+                return Task::perform(task, Message::WallpaperSet);
+            }
+            Message::WallpaperSet(Ok(())) => {}
+            Message::WallpaperSet(Err(error)) => {
+                self.notify_errors("Wallpaper not applied", vec![error]);
+            }
+            Message::DismissNotification(index) => {
+                if index < self.notifications.len() {
+                    self.notifications.remove(index);
+                }
             }
             Message::ShiftHeld(held) => {
                 self.canvas.set_shift_state(held);
             }
+            Message::CtrlHeld(held) => {
+                self.canvas.set_ctrl_state(held);
+            }
+            Message::GroupSelection => {
+                self.canvas.group_selection();
+            }
+            Message::UngroupSelection => {
+                self.canvas.ungroup_selection();
+            }
+            Message::Ungroup(id) => {
+                self.canvas.ungroup(id);
+            }
+            Message::RenameLayer(id, name) => {
+                if let Some(layer) = self.canvas.find_layer_mut(id) {
+                    layer.name = name;
+                }
+            }
+            Message::ToggleLayerExpanded(id) => {
+                if let Some(layer) = self.canvas.find_layer_mut(id) {
+                    layer.expanded = !layer.expanded;
+                }
+            }
+            Message::ToggleLayerHidden(id) => {
+                self.canvas.toggle_layer_hidden(id);
+            }
+            Message::ToggleLayerLocked(id) => {
+                self.canvas.toggle_layer_locked(id);
+            }
+            Message::LayerOpacityChanged(id, opacity) => {
+                if let Some(layer) = self.canvas.find_layer_mut(id) {
+                    layer.opacity = opacity;
+                }
+            }
+            Message::LayerFrameChanged(id, frame) => {
+                if let Some(layer) = self.canvas.find_layer_mut(id) {
+                    layer.handler.set_frame(frame);
+                }
+                return self.load_pending_images();
+            }
+            Message::NudgeSelection(delta) => {
+                self.canvas.nudge_selection(delta);
+            }
+            Message::DeleteSelection => {
+                self.canvas.delete_selection();
+            }
+            Message::DuplicateSelection => {
+                self.canvas.duplicate_selection();
+                return self.load_pending_images();
+            }
+            Message::CopySelection => {
+                return self.copy_selection().unwrap_or_else(Task::none);
+            }
+            Message::CutSelection => {
+                if let Some(task) = self.copy_selection() {
+                    self.canvas.delete_selection();
+                    return task;
+                }
+            }
+            Message::Paste => {
+                return clipboard::read().map(Message::ClipboardRead);
+            }
+            Message::ClipboardRead(contents) => {
+                if let Some(layers) = contents.as_deref().and_then(project::from_clipboard) {
+                    let errors = self.canvas.paste_layers(&layers, Point::ORIGIN);
+                    self.notify_errors("Some layers couldn't be pasted", errors);
+                    return self.load_pending_images();
+                } else {
+                    return Task::perform(read_clipboard_image(), Message::ClipboardImageRead);
+                }
+            }
+            Message::ClipboardImageRead(Some(ClipboardImage(pixels))) => {
+                let pixels = Arc::unwrap_or_clone(pixels);
+                self.canvas
+                    .add_pixels_layer("Pasted image".to_string(), pixels);
+            }
+            Message::ClipboardImageRead(None) => {}
+            Message::ToggleAssetBrowser => {
+                self.browser_open = !self.browser_open;
+                if self.browser_open {
+                    return self.scan_library();
+                }
+            }
+            Message::AssetDockSelected(dock) => {
+                self.browser_dock = dock;
+            }
+            Message::AddLibraryFolder => {
+                let task = async {
+                    AsyncFileDialog::new()
+                        .pick_folder()
+                        .await
+                        .map(|folder| folder.path().to_path_buf())
+                };
+                return Task::perform(task, Message::LibraryFolderSelected);
+            }
+            Message::LibraryFolderSelected(Some(folder)) => {
+                self.library.add_folder(folder);
+                self.save_library();
+                self.browser_open = true;
+                return self.scan_library();
+            }
+            Message::LibraryFolderSelected(None) => {}
+            Message::RemoveLibraryFolder(folder) => {
+                self.library.folders.retain(|other| *other != folder);
+                self.save_library();
+                return self.scan_library();
+            }
+            Message::AssetsScanned(assets) => {
+                // Thumbnails of images that are gone, e.g. from removed folders, are dropped
+                // and ones still loading are ignored when they arrive.
+                let found: HashSet<&PathBuf> = assets.iter().collect();
+                self.asset_thumbnails.retain(|path, _| found.contains(path));
+                self.loading_thumbnails.retain(|path| found.contains(path));
+
+                let missing: Vec<PathBuf> = assets
+                    .iter()
+                    .filter(|path| {
+                        !self.asset_thumbnails.contains_key(*path)
+                            && !self.loading_thumbnails.contains(*path)
+                    })
+                    .cloned()
+                    .collect();
+                self.loading_thumbnails.extend(missing.iter().cloned());
+                self.assets = assets;
+                return load_thumbnails(missing);
+            }
+            Message::AssetThumbnailLoaded(path, thumbnail, cache_error) => {
+                let requested = self.loading_thumbnails.remove(&path);
+                if let Some(thumbnail) = thumbnail.filter(|_| requested) {
+                    self.asset_thumbnails.insert(path, thumbnail);
+                }
+                self.notify_errors("Thumbnails not cached", cache_error.into_iter().collect());
+            }
+            Message::AssetQueryChanged(query) => {
+                self.browser_filter.query = query;
+            }
+            Message::AssetFavoritesOnlyToggled(favorites_only) => {
+                self.browser_filter.favorites_only = favorites_only;
+            }
+            Message::AssetTagFilterSelected(tag) => {
+                self.browser_filter.tag = match tag {
+                    TagFilter::All => None,
+                    TagFilter::Tag(tag) => Some(tag),
+                };
+            }
+            Message::AssetPressed(path) => {
+                self.selected_asset = Some(path.clone());
+                self.canvas.dragged_asset = Some(path);
+            }
+            Message::AssetDragEnded => {
+                self.canvas.dragged_asset = None;
+            }
+            Message::ToggleAssetFavorite(path) => {
+                self.library.toggle_favorite(&path);
+                self.save_library();
+            }
+            Message::AssetTagDraftChanged(tag) => {
+                self.asset_tag_draft = tag;
+            }
+            Message::AddAssetTag => {
+                if let Some(path) = &self.selected_asset {
+                    self.library.add_tag(path, &self.asset_tag_draft);
+                    self.asset_tag_draft.clear();
+                    self.save_library();
+                }
+            }
+            Message::RemoveAssetTag(path, tag) => {
+                self.library.remove_tag(&path, &tag);
+                if self.browser_filter.tag.as_ref() == Some(&tag)
+                    && !self.library.all_tags().contains(&tag)
+                {
+                    self.browser_filter.tag = None;
+                }
+                self.save_library();
+            }
+            Message::OpenImportDialog => {
+                self.import_dialog_open = true;
+            }
+            Message::CloseImportDialog => {
+                self.import_dialog_open = false;
+            }
+            Message::ImportRecursiveToggled(recursive) => {
+                self.import_options.recursive = recursive;
+            }
+            Message::ImportMinResolutionSelected(min_resolution) => {
+                self.import_options.min_resolution = min_resolution;
+            }
+            Message::ImportOrientationSelected(orientation) => {
+                self.import_options.orientation = orientation;
+            }
+            Message::ImportModifiedAfterChanged(date) => {
+                self.import_options.modified_after =
+                    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok();
+                self.import_modified_after = date;
+            }
+            Message::ImportSortSelected(sort) => {
+                self.import_options.sort = sort;
+            }
+            Message::ImportLimitChanged(limit) => {
+                self.import_options.limit = limit.trim().parse().ok().filter(|limit| *limit > 0);
+                self.import_limit = limit;
+            }
+            Message::ImportRandomToggled(random) => {
+                self.import_options.random = random;
+            }
+            Message::ImportFolder => {
+                let task = async {
+                    AsyncFileDialog::new()
+                        .pick_folder()
+                        .await
+                        .map(|folder| folder.path().to_path_buf())
+                };
+                return Task::perform(task, Message::ImportFolderSelected);
+            }
+            Message::ImportFolderSelected(Some(folder)) => {
+                self.import_dialog_open = false;
+                let options = self.import_options.clone();
+                let task =
+                    async move { import::scan_folder(&folder, &options, &mut Rng::from_time()) };
+                return Task::perform(task, |(paths, errors)| {
+                    Message::FolderScanned(paths, errors)
+                });
+            }
+            Message::ImportFolderSelected(None) => {}
+            Message::FolderScanned(paths, errors) => {
+                let center = self.canvas.center();
+                for (index, path) in paths.into_iter().enumerate() {
+                    let offset = maker_canvas::DROP_CASCADE * (index % 8) as f32;
+                    self.canvas
+                        .add_image_layer_at(path, Point::new(center.x + offset, center.y + offset));
+                }
+                self.notify_errors("Some images couldn't be imported", errors);
+                return self.load_pending_images();
+            }
+            Message::SourceChanged(path) => {
+                self.canvas.refresh_source(&path);
+                return self.load_pending_images();
+            }
+            Message::OpenRelinkDialog => {
+                self.relink_dialog_open = true;
+            }
+            Message::CloseRelinkDialog => {
+                self.relink_dialog_open = false;
+            }
+            Message::SearchMissingFolder => {
+                let task = async {
+                    AsyncFileDialog::new()
+                        .pick_folder()
+                        .await
+                        .map(|folder| folder.path().to_path_buf())
+                };
+                return Task::perform(task, Message::MissingFolderSelected);
+            }
+            Message::MissingFolderSelected(Some(folder)) => {
+                let task = tokio::task::spawn_blocking(move || {
+                    let mut files = Vec::new();
+                    import::collect_files(&folder, true, &mut files);
+                    files
+                });
+                return Task::perform(task, |files| {
+                    Message::RelinkCandidatesFound(files.unwrap_or_default())
+                });
+            }
+            Message::MissingFolderSelected(None) => {}
+            Message::RelinkCandidatesFound(candidates) => {
+                let relinked = self.canvas.relink_missing(&candidates);
+                let missing = self.canvas.missing_layers();
+                // Relinked layers simply reappear, only images still missing need a notice.
+                if missing.is_empty() {
+                    self.relink_dialog_open = false;
+                    return self.load_pending_images();
+                }
+                self.notifications.push(Notification {
+                    title: format!(
+                        "Relinked {relinked} {}",
+                        if relinked == 1 { "image" } else { "images" }
+                    ),
+                    details: missing
+                        .iter()
+                        .map(|(_, name, _)| format!("{name} is still missing"))
+                        .collect(),
+                });
+                return self.load_pending_images();
+            }
+            Message::SaveProject => {
+                let task = async {
+                    AsyncFileDialog::new()
+                        .add_filter("Bg Maker project", &[project::PROJECT_EXTENSION])
+                        .set_file_name(format!("wallpaper.{}", project::PROJECT_EXTENSION))
+                        .save_file()
+                        .await
+                        .map(|file| file.path().to_path_buf())
+                };
+                return Task::perform(task, Message::SaveProjectPathSelected);
+            }
+            Message::SaveProjectPathSelected(Some(path)) => {
+                let saved = self
+                    .canvas
+                    .to_project(self.layout_options.seed)
+                    .and_then(|project| {
+                        project::save(&path, &project).map_err(|error| Error::project(&path, error))
+                    });
+                if let Err(error) = saved {
+                    self.notify_errors("Project not saved", vec![error]);
+                }
+            }
+            Message::LoadProject => {
+                let task = async {
+                    AsyncFileDialog::new()
+                        .add_filter("Bg Maker project", &[project::PROJECT_EXTENSION])
+                        .pick_file()
+                        .await
+                        .map(|file| file.path().to_path_buf())
+                };
+                return Task::perform(task, Message::LoadProjectPathSelected);
+            }
+            Message::LoadProjectPathSelected(Some(path)) => match project::load(&path) {
+                Ok(project) => {
+                    self.layout_options.seed = project.layout_seed;
+                    let errors = self.canvas.load_project(&project);
+                    self.notify_errors("Some layers couldn't be loaded", errors);
+                    return self.load_pending_images();
+                }
+                Err(error) => {
+                    self.notify_errors("Project not loaded", vec![Error::project(&path, error)]);
+                }
+            },
+            Message::SaveProjectPathSelected(None) | Message::LoadProjectPathSelected(None) => {}
+            Message::CycleSelection(backwards) => {
+                self.canvas.cycle_selection(backwards);
+                self.property_draft = None;
+            }
+            Message::KeyPressed(key, modifiers) => {
+                let Some(chord) = Chord::from_event(&key, modifiers) else {
+                    return Task::none();
+                };
+
+                if let Some(action) = self.recording_action {
+                    if chord.is_modifier_only() {
+                        return Task::none();
+                    }
+                    self.recording_action = None;
+
+                    let conflicts = self.keymap.conflicts(action, &chord);
+                    if conflicts.is_empty() {
+                        self.bind(action, chord);
+                    } else {
+                        self.pending_rebind = Some((action, chord, conflicts));
+                    }
+                    return Task::none();
+                }
+
+                if self.shortcut_settings_open {
+                    return Task::none();
+                }
+
+                // Keys reach an open palette through `PaletteKeyPressed` instead.
+                if self.palette_query.is_some() {
+                    return Task::none();
+                }
+
+                match self.keymap.action_for(&chord) {
+                    Some(Action::Deselect) if self.shortcuts_overlay_open => {
+                        self.shortcuts_overlay_open = false;
+                    }
+                    Some(action) => return self.update(action.message()),
+                    None => {}
+                }
+            }
+            Message::PaletteKeyPressed(key, modifiers) => {
+                use keyboard::key::{Key, Named};
+
+                let Some(query) = &self.palette_query else {
+                    return Task::none();
+                };
+                let commands = palette::commands();
+                let last = palette::search(&commands, query).len().saturating_sub(1);
+                self.palette_selected = self.palette_selected.min(last);
+
+                match key.as_ref() {
+                    Key::Named(Named::ArrowDown) => {
+                        self.palette_selected = (self.palette_selected + 1).min(last);
+                    }
+                    Key::Named(Named::ArrowUp) => {
+                        self.palette_selected = self.palette_selected.saturating_sub(1);
+                    }
+                    Key::Named(Named::Escape) => self.palette_query = None,
+                    _ if Chord::from_event(&key, modifiers).is_some_and(|chord| {
+                        self.keymap.action_for(&chord) == Some(Action::CommandPalette)
+                    }) =>
+                    {
+                        self.palette_query = None;
+                    }
+                    _ => {}
+                }
+            }
+            Message::ToggleShortcutsOverlay => {
+                self.shortcuts_overlay_open = !self.shortcuts_overlay_open;
+            }
+            Message::OpenShortcutSettings => {
+                self.shortcuts_overlay_open = false;
+                self.shortcut_settings_open = true;
+            }
+            Message::CloseShortcutSettings => {
+                self.shortcut_settings_open = false;
+                self.recording_action = None;
+                self.pending_rebind = None;
+            }
+            Message::StartRebind(action) => {
+                self.pending_rebind = None;
+                self.recording_action = Some(action);
+            }
+            Message::ConfirmRebind => {
+                if let Some((action, chord, _)) = self.pending_rebind.take() {
+                    self.bind(action, chord);
+                }
+            }
+            Message::CancelRebind => {
+                self.recording_action = None;
+                self.pending_rebind = None;
+            }
+            Message::RemoveChord(action, chord) => {
+                self.keymap.unbind(action, &chord);
+                self.save_keymap();
+            }
+            Message::TogglePalette => {
+                if self.palette_query.take().is_none() {
+                    self.palette_query = Some(String::new());
+                    self.palette_selected = 0;
+                    return text_input::focus(palette_input_id());
+                }
+            }
+            Message::PaletteQueryChanged(query) => {
+                self.palette_query = Some(query);
+                self.palette_selected = 0;
+            }
+            Message::PaletteSubmitted => {
+                let query = self.palette_query.clone().unwrap_or_default();
+                let commands = palette::commands();
+                let results = palette::search(&commands, &query);
+
+                if let Some(command) =
+                    results.get(self.palette_selected.min(results.len().saturating_sub(1)))
+                {
+                    let message = command.message.clone();
+                    self.palette_query = None;
+                    return self.update(message);
+                }
+            }
+            Message::PaletteCommand(message) => {
+                self.palette_query = None;
+                return self.update(*message);
+            }
+            Message::CanvasSizeSelected(size) => {
+                self.canvas.set_size(size);
+            }
+            Message::FileDropped(path, position, index) => {
+                if index == 0 {
+                    self.dropped_images = 0;
+                }
+                let task = async move { import::collect_images(&path) };
+                return Task::perform(task, move |images| {
+                    Message::DroppedImagesCollected(images, position)
+                });
+            }
+            Message::DroppedImagesCollected(images, position) => {
+                let position = position.unwrap_or_else(|| self.canvas.center());
+
+                for image in images {
+                    let offset = maker_canvas::DROP_CASCADE * (self.dropped_images % 8) as f32;
+                    self.dropped_images += 1;
+                    self.canvas.add_image_layer_at(
+                        image,
+                        Point::new(position.x + offset, position.y + offset),
+                    );
+                }
+                return self.load_pending_images();
+            }
+            Message::CursorMoved(position) => {
+                self.cursor_position = position;
+            }
+            Message::OpenContextMenu(id) => {
+                if let Some(index) = self.canvas.layer_index(id) {
+                    let layer = &self.canvas.layers[index];
+                    if !layer.is_selected && layer.is_selectable() {
+                        self.canvas.select_layer(index);
+                        self.property_draft = None;
+                    }
+                }
+                self.context_menu = Some((id, self.cursor_position));
+            }
+            Message::CloseContextMenu => {
+                self.context_menu = None;
+            }
+            Message::ContextMenuCommand(message) => {
+                self.context_menu = None;
+                return self.update(*message);
+            }
+            Message::FlipSelection(axis) => {
+                self.canvas.flip_selection(axis);
+            }
+            Message::ResetSelectionSize => {
+                self.canvas.reset_selection_size();
+                return self.load_pending_images();
+            }
+            Message::ReplaceImage(id) => {
+                let task = async {
+                    AsyncFileDialog::new()
+                        .add_filter("image", &import::IMAGE_EXTENSIONS)
+                        .pick_file()
+                        .await
+                        .map(|file| file.path().to_path_buf())
+                };
+                return Task::perform(task, move |path| Message::ReplacementSelected(id, path));
+            }
+            Message::ReplacementSelected(id, Some(path)) => {
+                if let Err(error) = self.canvas.replace_layer_source(id, path) {
+                    self.notify_errors("Image not replaced", vec![error]);
+                }
+                return self.load_pending_images();
+            }
+            Message::ReplacementSelected(_, None) => {}
+            Message::RevealSource(id) => {
+                let path = self
+                    .canvas
+                    .find_layer(id)
+                    .and_then(|layer| layer.handler.source_path());
+                let error = path.and_then(|path| {
+                    utils::reveal_in_file_manager(path)
+                        .err()
+                        .map(|error| Error::Reveal {
+                            path: path.clone(),
+                            reason: error.to_string(),
+                        })
+                });
+                self.notify_errors("File not shown", error.into_iter().collect());
+            }
+            Message::ResetShortcuts => {
+                self.keymap = Keymap::default();
+                self.save_keymap();
+            }
+            Message::PropertyEdited(id, property, value) => {
+                if let Ok(number) = value.trim().parse::<f32>() {
+                    if number.is_finite() {
+                        self.apply_property(id, property, number);
+                    }
+                }
+                self.property_draft = self.canvas.find_layer(id).map(|layer| PropertyDraft {
+                    id,
+                    property,
+                    text: value,
+                    rect: layer.handler.get_rect(),
+                    rotation: layer.handler.get_rotation(),
+                });
+                return self.load_pending_images();
+            }
+            Message::PropertySubmitted => {
+                self.property_draft = None;
+            }
+            Message::AspectLockToggled(locked) => {
+                self.aspect_locked = locked;
+            }
             Message::LeftButtonReleased => {
                 self.canvas.on_left_button_released();
             }
-            Message::Undo => todo!(),
-            Message::Redo => todo!(),
+            // Only reachable through a keymap file that still binds them.
+            Message::Undo | Message::Redo => {}
             Message::LayerUp(id) => {
                 self.canvas.layer_up(id);
             }
             Message::LayerDown(id) => {
                 self.canvas.layer_down(id);
             }
+            Message::AutoLayout => {
+                self.canvas.auto_layout(&self.layout_options);
+                return self.load_pending_images();
+            }
+            Message::LayoutKindSelected(kind) => {
+                self.layout_options.kind = kind;
+            }
+            Message::LayoutGutterChanged(gutter) => {
+                self.layout_options.gutter = gutter;
+            }
+            Message::LayoutMarginChanged(margin) => {
+                self.layout_options.margin = margin;
+            }
+            Message::LayoutCornerRadiusChanged(radius) => {
+                self.layout_options.corner_radius = radius;
+            }
+            Message::LayoutCropToggled(crop) => {
+                self.layout_options.crop_to_fill = crop;
+            }
+            Message::LayoutWeightingSelected(weighting) => {
+                self.layout_options.weighting = weighting;
+            }
+            Message::ShuffleLayout => {
+                self.layout_options.seed = Rng::from_time().next_u64();
+                self.canvas.auto_layout(&self.layout_options);
+                return self.load_pending_images();
+            }
+            Message::LayerImportanceChanged(id, importance) => {
+                self.canvas.set_layer_importance(id, importance);
+            }
+            Message::ScatterMinScaleChanged(scale) => {
+                self.layout_options.scatter.min_scale = scale;
+            }
+            Message::ScatterMaxScaleChanged(scale) => {
+                self.layout_options.scatter.max_scale = scale;
+            }
+            Message::ScatterRotationChanged(rotation) => {
+                self.layout_options.scatter.max_rotation = rotation;
+            }
+            Message::ScatterPolaroidToggled(polaroid) => {
+                self.layout_options.scatter.polaroid = polaroid;
+            }
+            Message::ScatterShadowToggled(shadow) => {
+                self.layout_options.scatter.shadow = shadow;
+            }
+            Message::TidyUp => {
+                self.canvas.tidy_up(self.layout_options.gutter);
+                return self.load_pending_images();
+            }
+            Message::AlignSelection(align) => {
+                self.canvas.align_selection(align, self.align_target);
+            }
+            Message::AlignTargetSelected(target) => {
+                self.align_target = target;
+            }
+            Message::DistributeSelection(axis) => {
+                self.canvas.distribute_selection(axis);
+            }
             _ => return Task::none(),
         }
 
@@ -150,81 +1161,1233 @@ impl BgMaker {
     }
 
     pub fn view(&self) -> Element<Message> {
-        column![
+        if self.shortcut_settings_open {
+            return self.shortcut_settings();
+        }
+
+        let mut toolbar = row![
+            button("Save project").on_press(Message::SaveProject),
+            button("Load project").on_press(Message::LoadProject),
+            button("Add Images").on_press(Message::AddImage),
+            button("Add Folder").on_press(Message::OpenImportDialog),
+            button("Export to PNG").on_press(Message::SaveAsPng),
+            button("Save & Apply").on_press(Message::SaveApply),
+            button("Assets").on_press(Message::ToggleAssetBrowser),
+            button("Shortcuts").on_press(Message::OpenShortcutSettings),
+        ]
+        .spacing(4);
+        let missing = self.canvas.missing_layers().len();
+        if missing > 0 {
+            toolbar = toolbar.push(
+                button(text(format!("Relink missing ({missing})")))
+                    .style(button::danger)
+                    .on_press(Message::OpenRelinkDialog),
+            );
+        }
+
+        let editor = column![
+            toolbar,
+            self.layout_controls(),
+            self.arrange_controls(),
+            self.workspace(),
+        ];
+
+        let mut view = stack![editor];
+        if self.shortcuts_overlay_open {
+            view = view.push(self.shortcuts_overlay());
+        }
+        if self.import_dialog_open {
+            view = view.push(self.import_dialog());
+        }
+        if self.relink_dialog_open {
+            view = view.push(self.relink_dialog());
+        }
+        if let Some((id, position)) = self.context_menu {
+            view = view.push(self.context_menu(id, position));
+        }
+        if !self.notifications.is_empty() {
+            view = view.push(self.notifications());
+        }
+        if let Some(query) = &self.palette_query {
+            view = view.push(self.command_palette(query));
+        }
+
+        view.into()
+    }
+
+    /// Stack of notifications in the bottom right corner, newest at the bottom.
+    fn notifications(&self) -> Element<Message> {
+        const MAX_DETAILS: usize = 5;
+
+        let cards = column(
+            self.notifications
+                .iter()
+                .enumerate()
+                .map(|(index, notification)| {
+                    let mut details = column(
+                        notification
+                            .details
+                            .iter()
+                            .take(MAX_DETAILS)
+                            .map(|detail| text(detail).size(12).into()),
+                    )
+                    .spacing(2);
+                    if notification.details.len() > MAX_DETAILS {
+                        details = details.push(
+                            text(format!(
+                                "and {} more",
+                                notification.details.len() - MAX_DETAILS
+                            ))
+                            .size(12),
+                        );
+                    }
+
+                    container(
+                        column![
+                            row![
+                                text(&notification.title).size(14).width(Length::Fill),
+                                button(text("×").size(14))
+                                    .style(button::text)
+                                    .on_press(Message::DismissNotification(index)),
+                            ]
+                            .align_y(Alignment::Center),
+                            details,
+                        ]
+                        .spacing(4),
+                    )
+                    .padding(8)
+                    .width(360)
+                    .style(styles::bordered_box)
+                    .into()
+                }),
+        )
+        .spacing(8);
+
+        container(cards)
+            .padding(16)
+            .align_right(Length::Fill)
+            .align_bottom(Length::Fill)
+            .into()
+    }
+
+    /// Shows `errors` under `title`, does nothing when there are none.
+    ///
+    /// Errors arriving one by one, like failed background loads, join the newest
+    /// notification when it has the same title.
+    fn notify_errors(&mut self, title: &str, errors: Vec<Error>) {
+        if errors.is_empty() {
+            return;
+        }
+
+        if let Some(last) = self
+            .notifications
+            .last_mut()
+            .filter(|last| last.title == title)
+        {
+            last.details.extend(errors.iter().map(Error::to_string));
+            return;
+        }
+
+        self.notifications.push(Notification {
+            title: title.to_string(),
+            details: errors.iter().map(Error::to_string).collect(),
+        });
+    }
+
+    /// Layer actions shown at `position`, any click outside closes it.
+    fn context_menu(&self, id: Id, position: Point) -> Element<Message> {
+        let Some(layer) = self.canvas.find_layer(id) else {
+            return column![].into();
+        };
+
+        let entry = |label: &'static str, message: Option<Message>| {
+            button(text(label).size(14))
+                .style(button::text)
+                .width(Length::Fill)
+                .on_press_maybe(
+                    message.map(|message| Message::ContextMenuCommand(Box::new(message))),
+                )
+        };
+        let item = |label, message| entry(label, Some(message));
+        // Commands on the selection are disabled unless the clicked layer is part of it, so
+        // they never change layers that weren't clicked, like the selection behind a locked
+        // layer or a group's child.
+        let selection_item = |label, message| entry(label, layer.is_selected.then_some(message));
+
+        let mut items = column![
+            selection_item("Duplicate", Message::DuplicateSelection),
+            selection_item("Delete", Message::DeleteSelection),
+            selection_item("Bring to front", Message::BringToFront),
+            selection_item("Send to back", Message::SendToBack),
+            item("Move up", Message::LayerUp(id)),
+            item("Move down", Message::LayerDown(id)),
+            selection_item(
+                "Flip horizontally",
+                Message::FlipSelection(Axis::Horizontal)
+            ),
+            selection_item("Flip vertically", Message::FlipSelection(Axis::Vertical)),
+        ];
+
+        if !layer.is_group() {
+            items = items
+                .push(selection_item(
+                    "Reset to native size",
+                    Message::ResetSelectionSize,
+                ))
+                .push(item("Replace image...", Message::ReplaceImage(id)));
+        }
+        if layer.handler.source_path().is_some() {
+            items = items.push(item("Reveal source file", Message::RevealSource(id)));
+        }
+
+        items = items
+            .push(item(
+                if layer.locked { "Unlock" } else { "Lock" },
+                Message::ToggleLayerLocked(id),
+            ))
+            .push(item(
+                if layer.hidden { "Show" } else { "Hide" },
+                Message::ToggleLayerHidden(id),
+            ));
+
+        let menu = container(items.spacing(2))
+            .padding(4)
+            .width(200)
+            .style(styles::bordered_box);
+
+        mouse_area(
+            container(opaque(menu))
+                .width(Fill)
+                .height(Fill)
+                .padding(Padding::ZERO.left(position.x).top(position.y)),
+        )
+        .on_press(Message::CloseContextMenu)
+        .on_right_press(Message::CloseContextMenu)
+        .into()
+    }
+
+    /// Searchable list of every command, run with Enter or a click.
+    fn command_palette<'a>(&'a self, query: &'a str) -> Element<'a, Message> {
+        let commands = palette::commands();
+        let results = palette::search(&commands, query);
+        let selected = self.palette_selected.min(results.len().saturating_sub(1));
+
+        let entries = column(results.into_iter().enumerate().map(|(index, command)| {
+            let shortcut = command
+                .action
+                .and_then(|action| self.keymap.chords(action).first())
+                .map(Chord::to_string)
+                .unwrap_or_default();
+
+            button(
+                row![
+                    text(command.title.clone()).width(Length::Fill),
+                    text(shortcut).size(12),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .style(if index == selected {
+                button::primary
+            } else {
+                button::text
+            })
+            .width(Length::Fill)
+            .on_press(Message::PaletteCommand(Box::new(command.message.clone())))
+            .into()
+        }))
+        .spacing(2);
+
+        let palette = container(
+            column![
+                text_input("Type a command", query)
+                    .id(palette_input_id())
+                    .on_input(Message::PaletteQueryChanged)
+                    .on_submit(Message::PaletteSubmitted)
+                    .padding(8),
+                entries,
+            ]
+            .spacing(8),
+        )
+        .padding(8)
+        .width(520)
+        .style(styles::bordered_box);
+
+        opaque(
+            mouse_area(
+                container(opaque(palette))
+                    .center_x(Fill)
+                    .height(Fill)
+                    .padding(Padding::ZERO.top(80))
+                    .style(styles::backdrop),
+            )
+            .on_press(Message::TogglePalette),
+        )
+    }
+
+    fn bind(&mut self, action: Action, chord: Chord) {
+        self.keymap.bind(action, chord);
+        self.save_keymap();
+    }
+
+    /// Canvas and side panels, with the asset browser docked beside or below them.
+    fn workspace(&self) -> Element<Message> {
+        let canvas = container(self.canvas.view())
+            .style(|theme| {
+                let palette = theme.extended_palette();
+                Style {
+                    background: Some(palette.background.weak.color.into()),
+                    ..Style::default()
+                }
+            })
+            .width(Fill)
+            .height(Fill);
+        let panels = column![self.layer_panel(), self.properties_panel()].spacing(8);
+
+        if !self.browser_open {
+            return row![canvas, panels].into();
+        }
+
+        match self.browser_dock {
+            Dock::Left => row![self.asset_browser(), canvas, panels].spacing(8).into(),
+            Dock::Right => row![canvas, self.asset_browser(), panels].spacing(8).into(),
+            Dock::Bottom => column![row![canvas, panels], self.asset_browser()]
+                .spacing(8)
+                .into(),
+        }
+    }
+
+    /// Grid of the library's images, pressing one starts dragging it onto the canvas.
+    fn asset_browser(&self) -> Element<Message> {
+        let filter = &self.browser_filter;
+
+        let mut tag_options = vec![TagFilter::All];
+        tag_options.extend(self.library.all_tags().into_iter().map(TagFilter::Tag));
+        let selected_tag = filter.tag.clone().map_or(TagFilter::All, TagFilter::Tag);
+
+        let header = column![
+            row![
+                text("Assets").size(14).width(Length::Fill),
+                pick_list(
+                    Dock::ALL,
+                    Some(self.browser_dock),
+                    Message::AssetDockSelected
+                )
+                .text_size(12),
+                button(text("Add folder").size(12)).on_press(Message::AddLibraryFolder),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+            text_input("Search names and tags", &filter.query)
+                .on_input(Message::AssetQueryChanged)
+                .size(12),
+            row![
+                toggler(filter.favorites_only)
+                    .label("Favorites")
+                    .text_size(12)
+                    .on_toggle(Message::AssetFavoritesOnlyToggled),
+                pick_list(
+                    tag_options,
+                    Some(selected_tag),
+                    Message::AssetTagFilterSelected
+                )
+                .text_size(12),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(8),
+        ]
+        .spacing(6);
+
+        let folders = column(self.library.folders.iter().map(|folder| {
+            row![
+                text(folder.display().to_string())
+                    .size(10)
+                    .width(Length::Fill)
+                    .wrapping(text::Wrapping::None),
+                button(text("×").size(10))
+                    .style(button::text)
+                    .padding(0)
+                    .on_press(Message::RemoveLibraryFolder(folder.clone())),
+            ]
+            .align_y(Alignment::Center)
+            .into()
+        }))
+        .spacing(2);
+
+        let content: Element<Message> = if self.library.folders.is_empty() {
+            text("Add a folder of images to browse it here")
+                .size(12)
+                .into()
+        } else {
+            let tiles = self
+                .assets
+                .iter()
+                .filter(|path| self.library.matches(path, filter))
+                .map(|path| self.asset_tile(path));
+            scrollable(row(tiles).spacing(6).wrap()).height(Fill).into()
+        };
+
+        let mut browser = column![header, folders, content].spacing(8);
+        if let Some(details) = self.asset_details() {
+            browser = browser.push(details);
+        }
+
+        let browser = container(browser).padding(8).style(styles::bordered_box);
+        match self.browser_dock {
+            Dock::Left | Dock::Right => browser.width(280).height(Fill).into(),
+            Dock::Bottom => browser.width(Fill).height(260).into(),
+        }
+    }
+
+    fn asset_tile<'a>(&'a self, path: &'a PathBuf) -> Element<'a, Message> {
+        const TILE: f32 = 96.;
+
+        let thumbnail: Element<Message> = match self.asset_thumbnails.get(path) {
+            Some(handle) => iced::widget::image(handle).width(TILE).height(TILE).into(),
+            None => container(text("…").size(14))
+                .center_x(TILE)
+                .center_y(TILE)
+                .into(),
+        };
+        let favorite = button(
+            text(if self.library.is_favorite(path) {
+                "★"
+            } else {
+                "☆"
+            })
+            .size(14),
+        )
+        .style(button::text)
+        .padding(2)
+        .on_press(Message::ToggleAssetFavorite(path.clone()));
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let tile = container(
+            column![
+                stack![thumbnail, favorite],
+                text(name)
+                    .size(10)
+                    .width(TILE)
+                    .wrapping(text::Wrapping::None),
+            ]
+            .spacing(2),
+        )
+        .padding(2)
+        .style(if self.selected_asset.as_ref() == Some(path) {
+            styles::selected_bordered_box
+        } else {
+            styles::bordered_box
+        });
+
+        mouse_area(tile)
+            .on_press(Message::AssetPressed(path.clone()))
+            .interaction(mouse::Interaction::Grab)
+            .into()
+    }
+
+    /// Name and tags of the selected asset, with a field to tag it.
+    fn asset_details(&self) -> Option<Element<Message>> {
+        let path = self.selected_asset.as_ref()?;
+        let name = path.file_name()?.to_string_lossy().into_owned();
+
+        let tags = row(self.library.tags_of(path).map(|tag| {
+            button(text(format!("{tag} ×")).size(10))
+                .padding([1, 4])
+                .on_press(Message::RemoveAssetTag(path.clone(), tag.to_string()))
+                .into()
+        }))
+        .spacing(4)
+        .wrap();
+
+        Some(
+            column![
+                text(name).size(12),
+                tags,
+                text_input("Add tag", &self.asset_tag_draft)
+                    .on_input(Message::AssetTagDraftChanged)
+                    .on_submit(Message::AddAssetTag)
+                    .size(12),
+            ]
+            .spacing(4)
+            .into(),
+        )
+    }
+
+    /// Finds the library's images in the background.
+    fn scan_library(&self) -> Task<Message> {
+        let folders = self.library.folders.clone();
+        let task = async move {
+            tokio::task::spawn_blocking(move || library::scan(&folders))
+                .await
+                .unwrap_or_default()
+        };
+        Task::perform(task, Message::AssetsScanned)
+    }
+
+    fn save_library(&mut self) {
+        if let Err(error) = self.library.save() {
+            self.notify_errors("Library not saved", vec![Error::Library(error.to_string())]);
+        }
+    }
+
+    fn save_keymap(&mut self) {
+        if let Err(error) = self.keymap.save() {
+            self.notify_errors(
+                "Shortcuts not saved",
+                vec![Error::Keymap(error.to_string())],
+            );
+        }
+    }
+
+    fn chords_label(&self, action: Action) -> String {
+        let chords = self.keymap.chords(action);
+        if chords.is_empty() {
+            return "Unbound".to_string();
+        }
+
+        chords
+            .iter()
+            .map(Chord::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Filters and ordering for importing a folder, picked before choosing the folder.
+    fn import_dialog(&self) -> Element<Message> {
+        let options = &self.import_options;
+
+        let dialog = container(
+            column![
+                text("Add folder").size(20),
+                toggler(options.recursive)
+                    .label("Include subfolders")
+                    .on_toggle(Message::ImportRecursiveToggled),
+                row![
+                    pick_list(
+                        MinResolution::ALL,
+                        Some(options.min_resolution),
+                        Message::ImportMinResolutionSelected
+                    ),
+                    pick_list(
+                        Orientation::ALL,
+                        Some(options.orientation),
+                        Message::ImportOrientationSelected
+                    ),
+                ]
+                .spacing(8),
+                row![
+                    text("Modified since").width(120),
+                    text_input("YYYY-MM-DD", &self.import_modified_after)
+                        .on_input(Message::ImportModifiedAfterChanged),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(8),
+                pick_list(
+                    SortOrder::ALL,
+                    Some(options.sort),
+                    Message::ImportSortSelected
+                ),
+                row![
+                    text("Max images").width(120),
+                    text_input("No limit", &self.import_limit)
+                        .on_input(Message::ImportLimitChanged),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(8),
+                toggler(options.random)
+                    .label("Pick randomly when capped")
+                    .on_toggle(Message::ImportRandomToggled),
+                row![
+                    button("Cancel").on_press(Message::CloseImportDialog),
+                    button("Choose folder...").on_press(Message::ImportFolder),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12),
+        )
+        .padding(16)
+        .width(420)
+        .style(styles::bordered_box);
+
+        opaque(
+            mouse_area(center(opaque(dialog)).style(styles::backdrop))
+                .on_press(Message::CloseImportDialog),
+        )
+    }
+
+    /// Layers whose file was moved or deleted, each can be pointed at a new file or all of
+    /// them found by name in a folder.
+    fn relink_dialog(&self) -> Element<Message> {
+        let missing = self.canvas.missing_layers();
+
+        let list: Element<Message> = if missing.is_empty() {
+            text("No images are missing.").size(14).into()
+        } else {
+            scrollable(
+                column(missing.into_iter().map(|(id, name, path)| {
+                    row![
+                        column![
+                            text(name).size(14),
+                            text(path.display().to_string())
+                                .size(12)
+                                .style(text::secondary),
+                        ]
+                        .width(Fill),
+                        button(text("Locate...").size(14)).on_press(Message::ReplaceImage(id)),
+                    ]
+                    .align_y(Alignment::Center)
+                    .spacing(8)
+                    .into()
+                }))
+                .spacing(8),
+            )
+            .height(Length::Shrink)
+            .into()
+        };
+
+        let dialog = container(
+            column![
+                text("Missing images").size(20),
+                container(list).max_height(320),
+                row![
+                    button("Close").on_press(Message::CloseRelinkDialog),
+                    button("Search folder...").on_press(Message::SearchMissingFolder),
+                ]
+                .spacing(8),
+            ]
+            .spacing(12),
+        )
+        .padding(16)
+        .width(520)
+        .style(styles::bordered_box);
+
+        opaque(
+            mouse_area(center(opaque(dialog)).style(styles::backdrop))
+                .on_press(Message::CloseRelinkDialog),
+        )
+    }
+
+    /// Cheat sheet listing the active bindings on top of the editor.
+    fn shortcuts_overlay(&self) -> Element<Message> {
+        let bindings = column(Action::ALL.into_iter().map(|action| {
             row![
-                button("Save project"),
-                button("Load project"),
-                button("Add Images").on_press(Message::AddImage),
-                button("Export to PNG").on_press(Message::SaveAsPng),
-                button("Save & Apply").on_press(Message::SaveApply),
+                text(action.to_string()).size(14).width(Length::Fill),
+                text(self.chords_label(action)).size(14),
             ]
-            .spacing(4),
+            .spacing(16)
+            .into()
+        }))
+        .spacing(4);
+
+        let sheet = container(
+            column![
+                text("Keyboard shortcuts").size(20),
+                scrollable(bindings).height(Length::Shrink),
+                button("Edit shortcuts").on_press(Message::OpenShortcutSettings),
+            ]
+            .spacing(12),
+        )
+        .padding(16)
+        .width(420)
+        .max_height(600)
+        .style(styles::bordered_box);
+
+        opaque(
+            mouse_area(center(opaque(sheet)).style(styles::backdrop))
+                .on_press(Message::ToggleShortcutsOverlay),
+        )
+    }
+
+    /// Screen for rebinding every action, rejecting silent conflicts.
+    fn shortcut_settings(&self) -> Element<Message> {
+        let rows = column(Action::ALL.into_iter().map(|action| {
+            let binding: Element<Message> = if self.recording_action == Some(action) {
+                row![
+                    text("Press a key...").size(14),
+                    button("Cancel").on_press(Message::CancelRebind),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(8)
+                .into()
+            } else {
+                let chords = self.keymap.chords(action).iter().map(|chord| {
+                    row![
+                        text(chord.to_string()).size(14),
+                        button(text("×").size(12))
+                            .padding([0, 4])
+                            .on_press(Message::RemoveChord(action, chord.clone())),
+                    ]
+                    .align_y(Alignment::Center)
+                    .spacing(2)
+                    .into()
+                });
+
+                row(chords)
+                    .push(button("Add").on_press(Message::StartRebind(action)))
+                    .align_y(Alignment::Center)
+                    .spacing(8)
+                    .into()
+            };
+
+            container(
+                row![text(action.to_string()).width(Length::Fill), binding]
+                    .align_y(Alignment::Center)
+                    .padding(4),
+            )
+            .style(styles::bordered_box)
+            .into()
+        }))
+        .spacing(4)
+        .width(520);
+
+        let mut content = column![
             row![
-                container(self.canvas.view())
-                    .style(|theme| {
-                        let palette = theme.extended_palette();
-                        Style {
-                            background: Some(palette.background.weak.color.into()),
-                            ..Style::default()
+                button("Back").on_press(Message::CloseShortcutSettings),
+                text("Keyboard shortcuts").size(20),
+                button("Reset to defaults").on_press(Message::ResetShortcuts),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(12),
+        ]
+        .spacing(12);
+
+        if let Some((action, chord, conflicts)) = &self.pending_rebind {
+            let used_by = conflicts
+                .iter()
+                .map(Action::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            content = content.push(
+                container(
+                    row![
+                        text(format!(
+                            "{chord} is already used by {used_by}. Move it to {action}?"
+                        ))
+                        .width(Length::Fill),
+                        button("Replace").on_press(Message::ConfirmRebind),
+                        button("Cancel").on_press(Message::CancelRebind),
+                    ]
+                    .align_y(Alignment::Center)
+                    .spacing(8)
+                    .padding(8),
+                )
+                .width(520)
+                .style(styles::selected_bordered_box),
+            );
+        }
+
+        content
+            .push(scrollable(rows).height(Fill))
+            .padding(16)
+            .into()
+    }
+
+    /// Decodes the images of an export to `path` at full resolution in the background, the
+    /// canvas is rendered once `on_decoded` delivers them.
+    fn decode_for_export(
+        &mut self,
+        path: PathBuf,
+        on_decoded: fn(PathBuf, Result<ExportPixels, Error>) -> Message,
+    ) -> Task<Message> {
+        let loads = match self.canvas.export_loads(&path) {
+            Ok(loads) => loads,
+            Err(error) => {
+                self.notify_errors("Export failed", vec![error]);
+                return Task::none();
+            }
+        };
+
+        let task = async move {
+            let decoded_path = path.clone();
+            let pixels = tokio::task::spawn_blocking(move || {
+                maker_canvas::decode_for_export(&decoded_path, &loads)
+            })
+            .await
+            .unwrap_or_else(|error| Err(Error::export(&path, error)));
+            (path, pixels)
+        };
+        Task::perform(task, move |(path, pixels)| on_decoded(path, pixels))
+    }
+
+    /// Decodes the previews of placeholder layers in the background.
+    fn load_pending_images(&mut self) -> Task<Message> {
+        Task::batch(
+            self.canvas
+                .take_pending_loads()
+                .into_iter()
+                .map(|(id, request)| {
+                    let generation = request.generation;
+                    let task = async move {
+                        let path = request.path.clone();
+                        tokio::task::spawn_blocking(move || decode::preview(&request))
+                            .await
+                            .unwrap_or_else(|error| Err(Error::image_load(&path, error)))
+                    };
+                    Task::perform(task, move |result| {
+                        Message::ImageDecoded(id, generation, result)
+                    })
+                }),
+        )
+    }
+
+    /// Puts the selected layers on the system clipboard so they can be pasted into this
+    /// or another open project, `None` when they couldn't be copied.
+    fn copy_selection(&mut self) -> Option<Task<Message>> {
+        let layers = match self.canvas.copy_selection() {
+            Ok(layers) => layers,
+            Err(error) => {
+                self.notify_errors("Layers not copied", vec![error]);
+                return None;
+            }
+        };
+        if layers.is_empty() {
+            return Some(Task::none());
+        }
+
+        Some(match project::to_clipboard(&layers) {
+            Some(contents) => clipboard::write(contents),
+            None => Task::none(),
+        })
+    }
+
+    fn apply_property(&mut self, id: Id, property: Property, value: f32) {
+        let aspect_locked = self.aspect_locked;
+        let Some(layer) = self.canvas.find_layer_mut(id) else {
+            return;
+        };
+        let mut rect = layer.handler.get_rect();
+        let aspect = rect.width / rect.height;
+
+        match property {
+            Property::X => rect.x = value,
+            Property::Y => rect.y = value,
+            Property::Width if value > 0. => {
+                rect.width = value;
+                if aspect_locked {
+                    rect.height = value / aspect;
+                }
+            }
+            Property::Height if value > 0. => {
+                rect.height = value;
+                if aspect_locked {
+                    rect.width = value * aspect;
+                }
+            }
+            Property::Rotation => {
+                layer.handler.set_rotation(value.to_radians());
+                return;
+            }
+            _ => return,
+        }
+
+        layer.handler.set_rect(rect);
+    }
+
+    /// Numeric position, size, rotation and opacity of the selected layer.
+    fn properties_panel(&self) -> Element<Message> {
+        let Some(layer) = self.canvas.selected_layer() else {
+            return container(text("Select a layer to edit its properties").size(12))
+                .padding(8)
+                .width(300)
+                .style(styles::bordered_box)
+                .into();
+        };
+
+        let id = layer.id;
+        let rect = layer.handler.get_rect();
+        let rotation = layer.handler.get_rotation();
+        let field = |label: &'static str, property: Property, value: f32| {
+            let value = match &self.property_draft {
+                // Drags, nudges and arranging change the layer without the field.
+                Some(draft)
+                    if draft.id == id
+                        && draft.property == property
+                        && draft.rect == rect
+                        && draft.rotation == rotation =>
+                {
+                    draft.text.clone()
+                }
+                _ => format!("{}", (value * 100.).round() / 100.),
+            };
+
+            row![
+                text(label).size(12).width(16),
+                text_input(label, &value)
+                    .on_input(move |value| Message::PropertyEdited(id, property, value))
+                    .on_submit(Message::PropertySubmitted)
+                    .size(12)
+                    .width(Length::Fill),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(4)
+        };
+
+        let mut panel = column![
+            text(layer.get_name()).size(14),
+            row![
+                field("X", Property::X, rect.x),
+                field("Y", Property::Y, rect.y)
+            ]
+            .spacing(8),
+            row![
+                field("W", Property::Width, rect.width),
+                field("H", Property::Height, rect.height)
+            ]
+            .spacing(8),
+            toggler(self.aspect_locked)
+                .label("Lock aspect ratio")
+                .text_size(12)
+                .on_toggle(Message::AspectLockToggled),
+        ]
+        .spacing(6);
+
+        if let Some(rotation) = layer.handler.get_rotation() {
+            panel = panel.push(field("R", Property::Rotation, rotation.to_degrees()));
+        }
+
+        panel = panel.push(
+            row![
+                text("Opacity").size(12),
+                slider(0.0..=1.0, layer.opacity, move |opacity| {
+                    Message::LayerOpacityChanged(id, opacity)
+                })
+                .step(0.01),
+                text(format!("{:.0}%", layer.opacity * 100.)).size(12),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+
+        let frame_count = layer.handler.frame_count();
+        if frame_count > 1 {
+            let frame = layer.handler.frame();
+            panel = panel.push(
+                row![
+                    text("Frame").size(12),
+                    slider(0..=(frame_count - 1) as u32, frame as u32, move |frame| {
+                        Message::LayerFrameChanged(id, frame as usize)
+                    }),
+                    text(format!("{} / {frame_count}", frame + 1)).size(12),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(6),
+            );
+        }
+
+        if !layer.is_group() {
+            const BASE_DPI: f32 = 96.;
+            let native = layer.handler.get_native_size();
+            // Source pixels available for every exported pixel, below 1 the image gets upscaled
+            // and its DPI drops under the base.
+            let density = native.width / (rect.width * maker_canvas::EXPORT_SCALE_FACTOR);
+
+            panel = panel
+                .push(text(format!("Native {} × {} px", native.width, native.height)).size(12))
+                .push(
+                    text(format!(
+                        "Scale {:.0}% · {:.0} DPI{}",
+                        rect.width / native.width * 100.,
+                        BASE_DPI * density,
+                        if density < 1. {
+                            " · upscaled on export"
+                        } else {
+                            ""
                         }
+                    ))
+                    .size(12),
+                );
+        }
+
+        container(panel)
+            .padding(8)
+            .width(300)
+            .style(styles::bordered_box)
+            .into()
+    }
+
+    fn layer_panel(&self) -> Element<Message> {
+        mouse_area(
+            scrollable(
+                column(
+                    self.canvas
+                        .layers
+                        .iter()
+                        .enumerate()
+                        .map(|(index, layer)| self.layer_row(layer, Some(index), 0)),
+                )
+                .spacing(8),
+            )
+            .width(300)
+            .height(Fill),
+        )
+        .on_release(Message::LayerDragEnded)
+        .on_exit(Message::LayerDragCancelled)
+        .into()
+    }
+
+    /// Row of a layer in the side panel followed by the rows of its children when it is
+    /// an expanded group. Only top-level rows get an `index` and can be selected.
+    fn layer_row<'a>(
+        &'a self,
+        layer: &'a Layer,
+        index: Option<usize>,
+        depth: u16,
+    ) -> Element<'a, Message> {
+        let small_button = |label: &'a str, message: Message| {
+            button(text(label).size(12).wrapping(text::Wrapping::None))
+                .on_press(message)
+                .padding([2, 4])
+        };
+
+        let mut header = row![].align_y(Alignment::Center).spacing(6);
+
+        if layer.is_group() {
+            header = header.push(small_button(
+                if layer.expanded { "-" } else { "+" },
+                Message::ToggleLayerExpanded(layer.id),
+            ));
+        }
+
+        header = header.push(layer.handler.get_preview());
+
+        if layer.is_group() {
+            header = header
+                .push(
+                    text_input("Group name", layer.get_name())
+                        .on_input(move |name| Message::RenameLayer(layer.id, name))
+                        .size(12)
+                        .width(Length::Fill),
+                )
+                .push(
+                    slider(0.0..=1.0, layer.opacity, move |opacity| {
+                        Message::LayerOpacityChanged(layer.id, opacity)
                     })
-                    .width(Fill)
-                    .height(Fill),
-                container(
-                    column(self.canvas.layers.iter().map(|layer| {
-                        container(
-                            row![
-                                layer.handler.get_preview(),
-                                text(layer.get_name())
-                                    .width(Length::Fill)
-                                    .wrapping(text::Wrapping::Glyph),
-                                button(
-                                    container(text("x").size(16).wrapping(text::Wrapping::None))
-                                        .align_x(Alignment::Center)
-                                        .align_y(Alignment::Center)
-                                )
-                                .on_press(Message::RemoveImage(layer.id))
-                                .height(24)
-                                .width(24)
-                                .padding(0),
-                                column![
-                                    button(
-                                        container(text("U").size(12).wrapping(text::Wrapping::None))
-                                            .align_x(Alignment::Center)
-                                            .align_y(Alignment::Center)
-                                    )
-                                    .height(10)
-                                    .on_press(Message::LayerUp(layer.id)),
-                                    button(
-                                        container(text("D").size(12).wrapping(text::Wrapping::Word))
-                                            .align_x(Alignment::Center)
-                                            .align_y(Alignment::Center)
-                                    )
-                                    .height(10)
-                                    .on_press(Message::LayerDown(layer.id))
-                                ]
-                                .spacing(4)
-                                .width(24)
-                                .height(24)
-                            ]
+                    .step(0.05)
+                    .width(48),
+                )
+                .push(small_button("Ungroup", Message::Ungroup(layer.id)));
+        } else {
+            header = header.push(
+                text(layer.get_name())
+                    .width(Length::Fill)
+                    .wrapping(text::Wrapping::Glyph),
+            );
+        }
+
+        header = header
+            .push(small_button(
+                if layer.hidden { "Show" } else { "Hide" },
+                Message::ToggleLayerHidden(layer.id),
+            ))
+            .push(small_button(
+                if layer.locked { "Unlock" } else { "Lock" },
+                Message::ToggleLayerLocked(layer.id),
+            ));
+
+        if index.is_some() {
+            if !layer.is_group() {
+                header = header.push(
+                    slider(0.5..=4.0, layer.importance, move |importance| {
+                        Message::LayerImportanceChanged(layer.id, importance)
+                    })
+                    .step(0.5)
+                    .width(48),
+                );
+            }
+
+            header = header.push(
+                button(
+                    container(text("x").size(16).wrapping(text::Wrapping::None))
+                        .align_x(Alignment::Center)
+                        .align_y(Alignment::Center),
+                )
+                .on_press(Message::RemoveImage(layer.id))
+                .height(24)
+                .width(24)
+                .padding(0),
+            );
+            header = header.push(
+                column![
+                    button(
+                        container(text("U").size(12).wrapping(text::Wrapping::None))
+                            .align_x(Alignment::Center)
                             .align_y(Alignment::Center)
-                            .padding(4)
-                            .height(36)
-                            .spacing(6),
-                        )
-                        .style(if layer.is_selected {
-                            styles::selected_bordered_box
-                        } else {
-                            styles::bordered_box
-                        })
-                        .into()
-                    }))
-                    .width(300)
-                    .height(Fill)
-                    .spacing(8),
-                ),
-            ],
+                    )
+                    .height(10)
+                    .on_press(Message::LayerUp(layer.id)),
+                    button(
+                        container(text("D").size(12).wrapping(text::Wrapping::Word))
+                            .align_x(Alignment::Center)
+                            .align_y(Alignment::Center)
+                    )
+                    .height(10)
+                    .on_press(Message::LayerDown(layer.id))
+                ]
+                .spacing(4)
+                .width(24)
+                .height(24),
+            );
+        }
+
+        let is_drop_target = index.is_some()
+            && self.drop_row == index
+            && self.dragged_row.is_some()
+            && self.dragged_row != index;
+        let row_container = container(header.padding(4).height(36)).style(if is_drop_target {
+            styles::drop_target_box
+        } else if layer.is_selected {
+            styles::selected_bordered_box
+        } else {
+            styles::bordered_box
+        });
+
+        let layer_row: Element<Message> = match index {
+            Some(index) => mouse_area(row_container)
+                .on_press(Message::LayerRowPressed(index))
+                .on_right_press(Message::OpenContextMenu(layer.id))
+                .on_enter(Message::LayerRowEntered(index))
+                .into(),
+            None => row_container.into(),
+        };
+
+        let mut rows =
+            column![container(layer_row).padding(Padding::ZERO.left(f32::from(depth) * 16.))]
+                .spacing(4);
+
+        if let Some(children) = layer.handler.children() {
+            if layer.expanded {
+                rows = rows.extend(
+                    children
+                        .iter()
+                        .map(|child| self.layer_row(child, None, depth + 1)),
+                );
+            }
+        }
+
+        rows.into()
+    }
+
+    fn layout_controls(&self) -> Element<Message> {
+        let options = &self.layout_options;
+        let mut controls = row![
+            pick_list(
+                LayoutKind::ALL,
+                Some(options.kind),
+                Message::LayoutKindSelected
+            ),
+            text("Margin"),
+            slider(0.0..=128.0, options.margin, Message::LayoutMarginChanged).width(100),
         ]
+        .align_y(Alignment::Center)
+        .spacing(8);
+
+        if options.kind == LayoutKind::Scatter {
+            let scatter = &options.scatter;
+            controls = controls.extend([
+                text("Size").into(),
+                slider(
+                    0.1..=1.0,
+                    scatter.min_scale,
+                    Message::ScatterMinScaleChanged,
+                )
+                .step(0.05)
+                .width(80)
+                .into(),
+                slider(
+                    0.1..=1.0,
+                    scatter.max_scale,
+                    Message::ScatterMaxScaleChanged,
+                )
+                .step(0.05)
+                .width(80)
+                .into(),
+                text("Tilt").into(),
+                slider(
+                    0.0..=45.0,
+                    scatter.max_rotation,
+                    Message::ScatterRotationChanged,
+                )
+                .width(80)
+                .into(),
+                toggler(scatter.polaroid)
+                    .label("Polaroid")
+                    .on_toggle(Message::ScatterPolaroidToggled)
+                    .into(),
+                toggler(scatter.shadow)
+                    .label("Shadow")
+                    .on_toggle(Message::ScatterShadowToggled)
+                    .into(),
+            ]);
+        } else {
+            controls = controls.extend([
+                text("Gutter").into(),
+                slider(0.0..=64.0, options.gutter, Message::LayoutGutterChanged)
+                    .width(100)
+                    .into(),
+                text("Radius").into(),
+                slider(
+                    0.0..=64.0,
+                    options.corner_radius,
+                    Message::LayoutCornerRadiusChanged,
+                )
+                .width(100)
+                .into(),
+            ]);
+        }
+
+        match options.kind {
+            LayoutKind::Mosaic => {
+                controls = controls.push(pick_list(
+                    Weighting::ALL,
+                    Some(options.weighting),
+                    Message::LayoutWeightingSelected,
+                ));
+            }
+            LayoutKind::Scatter => {}
+            _ => {
+                controls = controls.push(
+                    toggler(options.crop_to_fill)
+                        .label("Crop to fill")
+                        .on_toggle(Message::LayoutCropToggled),
+                );
+            }
+        }
+
+        controls = controls.push(button("Auto layout").on_press(Message::AutoLayout));
+
+        if matches!(options.kind, LayoutKind::Mosaic | LayoutKind::Scatter) {
+            controls = controls
+                .push(button("Shuffle").on_press(Message::ShuffleLayout))
+                .push(text(format!("Seed: {}", options.seed)));
+        }
+
+        controls = controls.push(button("Tidy up").on_press(Message::TidyUp));
+
+        controls.into()
+    }
+
+    fn arrange_controls(&self) -> Element<Message> {
+        row![pick_list(
+            AlignTarget::ALL,
+            Some(self.align_target),
+            Message::AlignTargetSelected
+        )]
+        .extend(Align::ALL.into_iter().map(|align| {
+            button(text(align.to_string()))
+                .on_press(Message::AlignSelection(align))
+                .into()
+        }))
+        .push(button("Distribute H").on_press(Message::DistributeSelection(Axis::Horizontal)))
+        .push(button("Distribute V").on_press(Message::DistributeSelection(Axis::Vertical)))
+        .push(button("To front").on_press(Message::BringToFront))
+        .push(button("To back").on_press(Message::SendToBack))
+        .push(button("Group").on_press(Message::GroupSelection))
+        .push(button("Ungroup").on_press(Message::UngroupSelection))
+        .align_y(Alignment::Center)
+        .spacing(4)
         .into()
     }
 
@@ -233,20 +2396,99 @@ impl BgMaker {
         let release =
             keyboard::on_key_release(|key, modifiers| handle_hotkey_release(key, modifiers));
 
-        Subscription::batch(vec![press, release])
+        let cursor = event::listen_with(|event, _status, _window| match event {
+            iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                Some(Message::CursorMoved(position))
+            }
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::AssetDragEnded)
+            }
+            _ => None,
+        });
+
+        // The focused query input captures Escape and typed chords, so an open palette listens
+        // regardless of capture status.
+        let palette = if self.palette_query.is_some() {
+            event::listen_with(|event, _status, _window| match event {
+                iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                    Some(Message::PaletteKeyPressed(key, modifiers))
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        let folders = self.canvas.source_folders();
+        let sources = if folders.is_empty() {
+            Subscription::none()
+        } else {
+            Subscription::run_with(folders, watcher::watch)
+        };
+
+        Subscription::batch(vec![press, release, cursor, palette, sources])
     }
 }
 
+/// Reads image data from the system clipboard, iced's clipboard only handles text.
+async fn read_clipboard_image() -> Option<ClipboardImage> {
+    let image = arboard::Clipboard::new().ok()?.get_image().ok()?;
+    let pixels = image::RgbaImage::from_raw(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.into_owned(),
+    )?;
+
+    Some(ClipboardImage(Arc::new(pixels)))
+}
+
+/// Loads thumbnails one after another in the background, each shown as soon as it is ready.
+fn load_thumbnails(paths: Vec<PathBuf>) -> Task<Message> {
+    if paths.is_empty() {
+        return Task::none();
+    }
+
+    let thumbnails = iced::stream::channel(16, move |mut output| async move {
+        for path in paths {
+            let loaded = path.clone();
+            let loaded = tokio::task::spawn_blocking(move || thumbnails::load(&loaded))
+                .await
+                .ok()
+                .and_then(Result::ok);
+            let (thumbnail, cache_error) = match loaded {
+                Some((thumbnail, cache_error)) => (
+                    Some(Handle::from_rgba(
+                        thumbnail.width(),
+                        thumbnail.height(),
+                        thumbnail.as_raw().clone(),
+                    )),
+                    cache_error,
+                ),
+                None => (None, None),
+            };
+
+            if output.send((path, thumbnail, cache_error)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Task::run(thumbnails, |(path, thumbnail, cache_error)| {
+        Message::AssetThumbnailLoaded(path, thumbnail, cache_error)
+    })
+}
+
+fn palette_input_id() -> InputId {
+    InputId::new("command-palette")
+}
+
+/// Tracks held modifiers, every other key is resolved through the keymap in `update`.
 fn handle_hotkey_pressed(key: keyboard::Key, modifiers: keyboard::Modifiers) -> Option<Message> {
     use keyboard::key::{self, Key};
     match key.as_ref() {
-        Key::Character("z") if modifiers.command() && modifiers.shift() => Some(Message::Redo),
-        Key::Character("z") if modifiers.command() => Some(Message::Undo),
-        Key::Named(key) => match key {
-            key::Named::Shift => Some(Message::ShiftHeld(true)),
-            _ => None,
-        },
-        _ => None,
+        Key::Named(key::Named::Shift) => Some(Message::ShiftHeld(true)),
+        Key::Named(key::Named::Control) => Some(Message::CtrlHeld(true)),
+        _ => Some(Message::KeyPressed(key, modifiers)),
     }
 }
 
@@ -255,6 +2497,7 @@ fn handle_hotkey_release(key: keyboard::Key, _modifiers: keyboard::Modifiers) ->
     match key.as_ref() {
         Key::Named(key) => match key {
             key::Named::Shift => Some(Message::ShiftHeld(false)),
+            key::Named::Control => Some(Message::CtrlHeld(false)),
             _ => None,
         },
         _ => None,
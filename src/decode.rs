@@ -0,0 +1,318 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
+
+use image::{
+    AnimationDecoder, DynamicImage, Frames, ImageDecoder, ImageReader, ImageResult, RgbaImage,
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    imageops::FilterType,
+    metadata::Orientation,
+};
+use resvg::{tiny_skia, usvg};
+
+use crate::{error::Error, thumbnails};
+
+/// Longest side an SVG is rasterized at, so huge layers don't allocate gigabytes.
+const MAX_RASTER_SIDE: f32 = 8192.;
+
+/// Longest side of the copy of an image that is drawn on screen, about a large monitor.
+const PREVIEW_SIDE: u32 = 2560;
+
+/// A file a layer needs decoded in the background.
+#[derive(Debug, Clone)]
+pub struct LoadRequest {
+    pub path: PathBuf,
+    pub frame: usize,
+    /// Frames of the file when a previous load counted them, counting decodes them all.
+    pub frame_count: Option<usize>,
+    /// Counts the loads of a layer, results of loads it requested since are stale.
+    pub generation: u64,
+    /// Pixel size an SVG file is rendered at, its intrinsic size up to [`PREVIEW_SIDE`]
+    /// when `None`.
+    pub raster_size: Option<(u32, u32)>,
+}
+
+/// Screen sized pixels of an image file, decoded in the background.
+#[derive(Clone)]
+pub struct Preview {
+    pub frame: usize,
+    pub frame_count: usize,
+    /// Size of the full resolution image.
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Arc<RgbaImage>,
+    /// Cached thumbnail of the file for lists.
+    pub thumbnail: Arc<RgbaImage>,
+    /// Why the thumbnail couldn't be cached, it is still shown.
+    pub cache_error: Option<Error>,
+}
+
+impl fmt::Debug for Preview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Preview({}x{}, frame {})",
+            self.width, self.height, self.frame
+        )
+    }
+}
+
+/// System fonts for text in SVG files, loaded once on first use.
+static FONTS: LazyLock<Arc<usvg::fontdb::Database>> = LazyLock::new(|| {
+    let mut fonts = usvg::fontdb::Database::new();
+    fonts.load_system_fonts();
+    Arc::new(fonts)
+});
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase)
+}
+
+/// SVG files have no pixel size of their own and are rasterized at the size they are shown.
+pub fn is_vector(path: &Path) -> bool {
+    extension(path).as_deref() == Some("svg")
+}
+
+/// Pixel size of an image file once its EXIF orientation is applied, the intrinsic size
+/// for SVG files.
+pub fn dimensions(path: &Path) -> Result<(u32, u32), Error> {
+    if is_vector(path) {
+        let size = parse_svg(path)?.size().to_int_size();
+        return Ok((size.width(), size.height()));
+    }
+
+    let mut decoder = decoder(path).map_err(|error| Error::image_load(path, error))?;
+    let (width, height) = decoder.dimensions();
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+
+    Ok(match orientation {
+        Orientation::Rotate90
+        | Orientation::Rotate270
+        | Orientation::Rotate90FlipH
+        | Orientation::Rotate270FlipH => (height, width),
+        _ => (width, height),
+    })
+}
+
+/// Decodes the requested frame of an image file and scales it down to at most
+/// [`PREVIEW_SIDE`], SVG files are rendered at the requested raster size.
+///
+/// This reads the whole file, call it off the update thread.
+pub fn preview(request: &LoadRequest) -> Result<Preview, Error> {
+    let (path, frame) = (request.path.as_path(), request.frame);
+
+    if is_vector(path) {
+        let (width, height) = dimensions(path)?;
+        let pixels = match request.raster_size {
+            Some((raster_width, raster_height)) => {
+                rasterize_svg(path, raster_width as f32, raster_height as f32)?
+            }
+            None => {
+                let scale = (PREVIEW_SIDE as f32 / width.max(height) as f32).min(1.);
+                rasterize_svg(path, width as f32 * scale, height as f32 * scale)?
+            }
+        };
+        let (thumbnail, cache_error) = thumbnail(path, &pixels);
+        return Ok(Preview {
+            frame: 0,
+            frame_count: 1,
+            width,
+            height,
+            thumbnail,
+            cache_error,
+            pixels: Arc::new(pixels),
+        });
+    }
+
+    let frame_count = request.frame_count.unwrap_or_else(|| frame_count(path));
+    let image = decode(path, frame, frame_count)?;
+
+    let (width, height) = (image.width(), image.height());
+    let image = if width.max(height) > PREVIEW_SIDE {
+        image.resize(PREVIEW_SIDE, PREVIEW_SIDE, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let pixels = image.into_rgba8();
+    let (thumbnail, cache_error) = thumbnail(path, &pixels);
+    Ok(Preview {
+        frame,
+        frame_count,
+        width,
+        height,
+        thumbnail,
+        cache_error,
+        pixels: Arc::new(pixels),
+    })
+}
+
+fn thumbnail(path: &Path, pixels: &RgbaImage) -> (Arc<RgbaImage>, Option<Error>) {
+    match thumbnails::cached(path) {
+        Some(thumbnail) => (thumbnail, None),
+        None => thumbnails::store(path, pixels),
+    }
+}
+
+/// Decodes the requested frame of an animated image, or the whole image, at its full
+/// size.
+pub fn full_resolution(request: &LoadRequest) -> Result<DynamicImage, Error> {
+    let path = request.path.as_path();
+    let frame_count = request.frame_count.unwrap_or_else(|| frame_count(path));
+    decode(path, request.frame, frame_count)
+}
+
+fn decode(path: &Path, frame: usize, frame_count: usize) -> Result<DynamicImage, Error> {
+    if frame_count > 1 {
+        decode_frame(path, frame).map(DynamicImage::ImageRgba8)
+    } else {
+        open(path)
+    }
+}
+
+/// Decodes a still image upright and converted to sRGB.
+pub fn open(path: &Path) -> Result<DynamicImage, Error> {
+    let load = || -> ImageResult<DynamicImage> {
+        let mut decoder = decoder(path)?;
+        let orientation = decoder.orientation()?;
+        let profile = decoder.icc_profile()?;
+
+        let mut image = DynamicImage::from_decoder(decoder)?;
+        if let Some(profile) = profile {
+            image = to_srgb(image, &profile);
+        }
+        image.apply_orientation(orientation);
+        Ok(image)
+    };
+
+    load().map_err(|error| Error::image_load(path, error))
+}
+
+fn decoder(path: &Path) -> ImageResult<impl ImageDecoder> {
+    ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()
+}
+
+/// Converts pixels described by an ICC profile to sRGB, unreadable profiles are ignored.
+fn to_srgb(image: DynamicImage, profile: &[u8]) -> DynamicImage {
+    let Some(input) = qcms::Profile::new_from_slice(profile, false) else {
+        return image;
+    };
+    let mut output = qcms::Profile::new_sRGB();
+    output.precache_output_transform();
+    let Some(transform) = qcms::Transform::new(
+        &input,
+        &output,
+        qcms::DataType::RGBA8,
+        qcms::Intent::default(),
+    ) else {
+        return image;
+    };
+
+    let mut pixels = image.into_rgba8();
+    transform.apply(&mut pixels);
+    DynamicImage::ImageRgba8(pixels)
+}
+
+/// Number of frames of an animated GIF or APNG, 1 for still images.
+pub fn frame_count(path: &Path) -> usize {
+    match animation_frames(path) {
+        Ok(Some(frames)) => frames.take_while(Result::is_ok).count().max(1),
+        _ => 1,
+    }
+}
+
+/// Decodes the frame at `index` of an animated GIF or APNG.
+pub fn decode_frame(path: &Path, index: usize) -> Result<RgbaImage, Error> {
+    let frame = match animation_frames(path) {
+        Ok(Some(mut frames)) => frames.nth(index),
+        Ok(None) => None,
+        Err(error) => return Err(Error::image_load(path, error)),
+    };
+
+    match frame {
+        Some(Ok(frame)) => Ok(frame.into_buffer()),
+        Some(Err(error)) => Err(Error::image_load(path, error)),
+        None => Err(Error::image_load(path, format!("no frame {}", index + 1))),
+    }
+}
+
+fn animation_frames(path: &Path) -> ImageResult<Option<Frames<'static>>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    match extension(path).as_deref() {
+        Some("gif") => Ok(Some(GifDecoder::new(reader)?.into_frames())),
+        Some("png" | "apng") => {
+            let decoder = PngDecoder::new(reader)?;
+            if decoder.is_apng()? {
+                Ok(Some(decoder.apng()?.into_frames()))
+            } else {
+                Ok(None)
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn parse_svg(path: &Path) -> Result<usvg::Tree, Error> {
+    let data = fs::read(path).map_err(|error| Error::image_load(path, error))?;
+    let options = usvg::Options {
+        resources_dir: path.parent().map(Path::to_path_buf),
+        fontdb: FONTS.clone(),
+        ..usvg::Options::default()
+    };
+
+    usvg::Tree::from_data(&data, &options).map_err(|error| Error::image_load(path, error))
+}
+
+/// Pixel size to render an SVG file at to cover `width` × `height` pixels.
+///
+/// Each side is rounded up to a power of two, so resizing a layer only renders it again
+/// once it outgrows its raster, and capped to [`MAX_RASTER_SIDE`].
+pub fn raster_size(width: f32, height: f32) -> (u32, u32) {
+    let bucket = |side: f32| (side.ceil().max(1.) as u32).next_power_of_two() as f32;
+    let (width, height) = (bucket(width), bucket(height));
+    let scale = (MAX_RASTER_SIDE / width.max(height)).min(1.);
+
+    (
+        (width * scale).max(1.) as u32,
+        (height * scale).max(1.) as u32,
+    )
+}
+
+/// Renders an SVG file stretched to `width` × `height` pixels.
+pub fn rasterize_svg(path: &Path, width: f32, height: f32) -> Result<RgbaImage, Error> {
+    let tree = parse_svg(path)?;
+    let scale = (MAX_RASTER_SIDE / width.max(height)).min(1.);
+    let width = (width * scale).ceil().max(1.) as u32;
+    let height = (height * scale).ceil().max(1.) as u32;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| Error::image_load(path, "invalid raster size"))?;
+    let size = tree.size();
+    let transform = tiny_skia::Transform::from_scale(
+        width as f32 / size.width(),
+        height as f32 / size.height(),
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // Pixmaps hold premultiplied alpha, images expect it straight.
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| Error::image_load(path, "invalid raster size"))
+}
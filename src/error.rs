@@ -0,0 +1,101 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Failures that are reported to the user instead of stopping the app.
+///
+/// Causes are kept as text so errors can be cloned into messages.
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// An image file couldn't be read or decoded.
+    ImageLoad {
+        path: PathBuf,
+        reason: String,
+    },
+    /// An image embedded in a project or the clipboard couldn't be decoded.
+    EmbeddedImage {
+        name: String,
+        reason: String,
+    },
+    Export {
+        path: PathBuf,
+        reason: String,
+    },
+    Wallpaper(String),
+    /// The asset library's folders, favorites or tags couldn't be written.
+    Library(String),
+    Project {
+        path: PathBuf,
+        reason: String,
+    },
+    /// A settings file couldn't be read, the defaults are used instead.
+    Settings {
+        file: &'static str,
+        reason: String,
+    },
+    /// The keyboard shortcuts couldn't be written.
+    Keymap(String),
+    /// A file couldn't be shown in the system's file manager.
+    Reveal {
+        path: PathBuf,
+        reason: String,
+    },
+    /// A thumbnail couldn't be written to the cache, it is still shown.
+    ThumbnailCache {
+        path: PathBuf,
+        reason: String,
+    },
+}
+
+impl Error {
+    pub fn image_load(path: &Path, reason: impl fmt::Display) -> Self {
+        Error::ImageLoad {
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn export(path: &Path, reason: impl fmt::Display) -> Self {
+        Error::Export {
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn project(path: &Path, reason: impl fmt::Display) -> Self {
+        Error::Project {
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ImageLoad { path, reason } => write!(f, "{}: {reason}", path.display()),
+            Error::EmbeddedImage { name, reason } => write!(f, "{name}: {reason}"),
+            Error::Export { path, reason } => {
+                write!(f, "Couldn't export {}: {reason}", path.display())
+            }
+            Error::Wallpaper(reason) => write!(f, "Couldn't set the wallpaper: {reason}"),
+            Error::Library(reason) => write!(f, "Couldn't save the library: {reason}"),
+            Error::Project { path, reason } => write!(f, "{}: {reason}", path.display()),
+            Error::Settings { file, reason } => write!(f, "Ignoring invalid {file}: {reason}"),
+            Error::Keymap(reason) => write!(f, "Couldn't save the shortcuts: {reason}"),
+            Error::Reveal { path, reason } => {
+                write!(f, "Couldn't show {}: {reason}", path.display())
+            }
+            Error::ThumbnailCache { path, reason } => {
+                write!(
+                    f,
+                    "Couldn't cache the thumbnail of {}: {reason}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
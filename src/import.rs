@@ -0,0 +1,426 @@
+use std::{fmt, fs, io::BufReader, path::Path, path::PathBuf, time::SystemTime};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+
+use crate::{decode, error::Error, rng::Rng};
+
+/// Extensions of the image files that can be added as layers.
+pub const IMAGE_EXTENSIONS: [&str; 12] = [
+    "png", "apng", "jpg", "jpeg", "webp", "tif", "tiff", "bmp", "tga", "qoi", "gif", "svg",
+];
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|supported| extension.eq_ignore_ascii_case(supported))
+        })
+}
+
+/// Images to add for a dropped path: the file itself, or the images directly inside a
+/// folder sorted by name.
+pub fn collect_images(path: &Path) -> Vec<PathBuf> {
+    if !path.is_dir() {
+        return if is_image(path) {
+            vec![path.to_path_buf()]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let mut images: Vec<PathBuf> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_image(path))
+                .collect()
+        })
+        .unwrap_or_default();
+    images.sort();
+
+    images
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Any,
+    Landscape,
+    Portrait,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 3] = [
+        Orientation::Any,
+        Orientation::Landscape,
+        Orientation::Portrait,
+    ];
+
+    fn accepts(self, width: u32, height: u32) -> bool {
+        match self {
+            Orientation::Any => true,
+            Orientation::Landscape => width >= height,
+            Orientation::Portrait => height >= width,
+        }
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Orientation::Any => "Any orientation",
+            Orientation::Landscape => "Landscape",
+            Orientation::Portrait => "Portrait",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Smallest image size to import, compared side by side regardless of orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinResolution {
+    Any,
+    Hd,
+    FullHd,
+    Qhd,
+    Uhd,
+}
+
+impl MinResolution {
+    pub const ALL: [MinResolution; 5] = [
+        MinResolution::Any,
+        MinResolution::Hd,
+        MinResolution::FullHd,
+        MinResolution::Qhd,
+        MinResolution::Uhd,
+    ];
+
+    /// Long and short side in pixels.
+    fn sides(self) -> (u32, u32) {
+        match self {
+            MinResolution::Any => (0, 0),
+            MinResolution::Hd => (1280, 720),
+            MinResolution::FullHd => (1920, 1080),
+            MinResolution::Qhd => (2560, 1440),
+            MinResolution::Uhd => (3840, 2160),
+        }
+    }
+
+    fn accepts(self, width: u32, height: u32) -> bool {
+        let (long, short) = self.sides();
+        width.max(height) >= long && width.min(height) >= short
+    }
+}
+
+impl fmt::Display for MinResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MinResolution::Any => "Any size",
+            MinResolution::Hd => "At least 720p",
+            MinResolution::FullHd => "At least 1080p",
+            MinResolution::Qhd => "At least 1440p",
+            MinResolution::Uhd => "At least 4K",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Name,
+    Modified,
+    /// EXIF capture time, images without one use their modification date.
+    Captured,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 3] = [SortOrder::Name, SortOrder::Modified, SortOrder::Captured];
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SortOrder::Name => "Sort by name",
+            SortOrder::Modified => "Sort by file date",
+            SortOrder::Captured => "Sort by capture time",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub recursive: bool,
+    pub min_resolution: MinResolution,
+    pub orientation: Orientation,
+    /// Only files modified on or after this day.
+    pub modified_after: Option<NaiveDate>,
+    pub sort: SortOrder,
+    /// Largest number of images to import.
+    pub limit: Option<usize>,
+    /// Whether a capped import picks a random subset instead of the first ones.
+    pub random: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            min_resolution: MinResolution::Any,
+            orientation: Orientation::Any,
+            modified_after: None,
+            sort: SortOrder::Name,
+            limit: None,
+            random: false,
+        }
+    }
+}
+
+struct Candidate {
+    path: PathBuf,
+    modified: SystemTime,
+}
+
+/// Images in `folder` that pass the filters of `options`, in the requested order, and
+/// the files that looked like images but couldn't be read.
+pub fn scan_folder(
+    folder: &Path,
+    options: &ImportOptions,
+    rng: &mut Rng,
+) -> (Vec<PathBuf>, Vec<Error>) {
+    let mut errors = Vec::new();
+    let mut files = Vec::new();
+    collect_files(folder, options.recursive, &mut files);
+
+    let modified_after = options.modified_after.and_then(|date| {
+        let midnight = date
+            .and_hms_opt(0, 0, 0)?
+            .and_local_timezone(Local)
+            .earliest()?;
+        Some(SystemTime::from(midnight))
+    });
+
+    let mut candidates: Vec<Candidate> = files
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            if modified_after.is_some_and(|after| modified < after) {
+                return None;
+            }
+
+            let (width, height) = match decode::dimensions(&path) {
+                Ok(dimensions) => dimensions,
+                Err(error) => {
+                    errors.push(error);
+                    return None;
+                }
+            };
+            if !options.min_resolution.accepts(width, height)
+                || !options.orientation.accepts(width, height)
+            {
+                return None;
+            }
+
+            Some(Candidate { path, modified })
+        })
+        .collect();
+
+    let limit = options.limit.unwrap_or(usize::MAX);
+    if options.random && limit < candidates.len() {
+        rng.shuffle(&mut candidates);
+        candidates.truncate(limit);
+    }
+    sort_candidates(&mut candidates, options.sort);
+    candidates.truncate(limit);
+
+    let paths = candidates
+        .into_iter()
+        .map(|candidate| candidate.path)
+        .collect();
+
+    (paths, errors)
+}
+
+/// Image files in `folder`, symlinked folders are not entered so links can't form a loop.
+pub fn collect_files(folder: &Path, recursive: bool, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return;
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if recursive {
+                collect_files(&path, recursive, files);
+            }
+        } else if is_image(&path) && (!file_type.is_symlink() || path.is_file()) {
+            // Links to files are followed, links to folders are skipped.
+            files.push(path);
+        }
+    }
+}
+
+fn sort_candidates(candidates: &mut [Candidate], order: SortOrder) {
+    match order {
+        SortOrder::Name => candidates.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortOrder::Modified => candidates.sort_by_key(|candidate| candidate.modified),
+        SortOrder::Captured => candidates.sort_by_cached_key(|candidate| {
+            capture_time(&candidate.path)
+                .unwrap_or_else(|| DateTime::<Local>::from(candidate.modified).naive_local())
+        }),
+    }
+}
+
+/// Reads the EXIF "DateTimeOriginal" of a photo.
+fn capture_time(path: &Path) -> Option<NaiveDateTime> {
+    let file = fs::File::open(path).ok()?;
+    let exif = exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()?;
+    let field = exif.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)?;
+
+    let exif::Value::Ascii(values) = &field.value else {
+        return None;
+    };
+    let time = exif::DateTime::from_ascii(values.first()?).ok()?;
+
+    NaiveDate::from_ymd_opt(time.year.into(), time.month.into(), time.day.into())?.and_hms_opt(
+        time.hour.into(),
+        time.minute.into(),
+        time.second.into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Empty folder for one test under the system's temporary folder.
+    fn scratch_folder(test: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("bg-maker-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    fn write_image(path: &Path, width: u32, height: u32) {
+        image::RgbaImage::new(width, height).save(path).unwrap();
+    }
+
+    fn file_names(paths: &[PathBuf]) -> Vec<&str> {
+        paths
+            .iter()
+            .filter_map(|path| path.file_name()?.to_str())
+            .collect()
+    }
+
+    #[test]
+    fn image_extensions_ignore_case() {
+        assert!(is_image(Path::new("photo.JPG")));
+        assert!(is_image(Path::new("folder/drawing.svg")));
+        assert!(!is_image(Path::new("notes.txt")));
+        assert!(!is_image(Path::new("png")));
+    }
+
+    #[test]
+    fn resolution_is_compared_regardless_of_orientation() {
+        assert!(MinResolution::FullHd.accepts(1080, 1920));
+        assert!(MinResolution::FullHd.accepts(1920, 1080));
+        assert!(!MinResolution::FullHd.accepts(1920, 1000));
+        assert!(MinResolution::Any.accepts(1, 1));
+    }
+
+    #[test]
+    fn square_images_have_both_orientations() {
+        assert!(Orientation::Landscape.accepts(30, 30));
+        assert!(Orientation::Portrait.accepts(30, 30));
+        assert!(!Orientation::Landscape.accepts(20, 40));
+        assert!(!Orientation::Portrait.accepts(40, 20));
+    }
+
+    #[test]
+    fn candidates_sort_by_name_or_date() {
+        let candidate = |name: &str, seconds: u64| Candidate {
+            path: PathBuf::from(name),
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+        };
+        let paths = |candidates: &[Candidate]| {
+            candidates
+                .iter()
+                .map(|candidate| candidate.path.clone())
+                .collect::<Vec<_>>()
+        };
+        let mut candidates = vec![
+            candidate("b.png", 1),
+            candidate("c.png", 3),
+            candidate("a.png", 2),
+        ];
+
+        sort_candidates(&mut candidates, SortOrder::Name);
+        assert_eq!(file_names(&paths(&candidates)), ["a.png", "b.png", "c.png"]);
+
+        sort_candidates(&mut candidates, SortOrder::Modified);
+        assert_eq!(file_names(&paths(&candidates)), ["b.png", "a.png", "c.png"]);
+
+        // Files without a capture time fall back to their modification date.
+        sort_candidates(&mut candidates, SortOrder::Name);
+        sort_candidates(&mut candidates, SortOrder::Captured);
+        assert_eq!(file_names(&paths(&candidates)), ["b.png", "a.png", "c.png"]);
+    }
+
+    #[test]
+    fn scan_folder_filters_sorts_and_limits() {
+        let folder = scratch_folder("scan");
+        write_image(&folder.join("b.png"), 40, 20);
+        write_image(&folder.join("a.png"), 20, 40);
+        write_image(&folder.join("c.png"), 30, 30);
+        fs::write(folder.join("notes.txt"), "not an image").unwrap();
+        fs::write(folder.join("broken.png"), "not an image either").unwrap();
+        fs::create_dir(folder.join("nested")).unwrap();
+        write_image(&folder.join("nested").join("d.png"), 50, 10);
+
+        let scan = |options: ImportOptions| scan_folder(&folder, &options, &mut Rng::new(0));
+
+        let (paths, errors) = scan(ImportOptions::default());
+        assert_eq!(file_names(&paths), ["a.png", "b.png", "c.png"]);
+        assert_eq!(errors.len(), 1);
+
+        let (paths, _) = scan(ImportOptions {
+            recursive: true,
+            orientation: Orientation::Landscape,
+            ..ImportOptions::default()
+        });
+        assert_eq!(file_names(&paths), ["b.png", "c.png", "d.png"]);
+
+        let (paths, _) = scan(ImportOptions {
+            limit: Some(2),
+            ..ImportOptions::default()
+        });
+        assert_eq!(file_names(&paths), ["a.png", "b.png"]);
+
+        let (paths, _) = scan(ImportOptions {
+            limit: Some(2),
+            random: true,
+            ..ImportOptions::default()
+        });
+        assert_eq!(paths.len(), 2);
+        assert!(paths.is_sorted());
+
+        assert_eq!(
+            file_names(&collect_images(&folder)),
+            ["a.png", "b.png", "broken.png", "c.png"]
+        );
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,443 @@
+use std::{collections::BTreeMap, fmt, fs, path::PathBuf};
+
+use iced::{
+    Point,
+    keyboard::{Key, Modifiers},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{bg_maker::Message, error::Error, utils};
+
+const KEYMAP_FILE: &str = "keymap.json";
+
+/// Name the "+" key is written with, a bare "+" would read as a separator.
+const PLUS_KEY: &str = "Plus";
+
+/// Named command a key chord can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Undo and redo have no history behind them yet, so they are left out of [`Action::ALL`]
+    /// and unbound; the variants stay so keymap files naming them still load.
+    Undo,
+    Redo,
+    SelectAll,
+    Deselect,
+    NextLayer,
+    PreviousLayer,
+    Group,
+    Ungroup,
+    BringToFront,
+    SendToBack,
+    Duplicate,
+    Copy,
+    Cut,
+    Paste,
+    Delete,
+    NudgeLeft,
+    NudgeRight,
+    NudgeUp,
+    NudgeDown,
+    NudgeLeftFar,
+    NudgeRightFar,
+    NudgeUpFar,
+    NudgeDownFar,
+    ShowShortcuts,
+    CommandPalette,
+}
+
+impl Action {
+    pub const ALL: [Action; 23] = [
+        Action::SelectAll,
+        Action::Deselect,
+        Action::NextLayer,
+        Action::PreviousLayer,
+        Action::Group,
+        Action::Ungroup,
+        Action::BringToFront,
+        Action::SendToBack,
+        Action::Duplicate,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::Delete,
+        Action::NudgeLeft,
+        Action::NudgeRight,
+        Action::NudgeUp,
+        Action::NudgeDown,
+        Action::NudgeLeftFar,
+        Action::NudgeRightFar,
+        Action::NudgeUpFar,
+        Action::NudgeDownFar,
+        Action::ShowShortcuts,
+        Action::CommandPalette,
+    ];
+
+    pub fn message(self) -> Message {
+        const NUDGE: f32 = 1.;
+        const NUDGE_FAR: f32 = 10.;
+
+        match self {
+            Action::Undo => Message::Undo,
+            Action::Redo => Message::Redo,
+            Action::SelectAll => Message::SelectAll,
+            Action::Deselect => Message::DeselectLayers,
+            Action::NextLayer => Message::CycleSelection(false),
+            Action::PreviousLayer => Message::CycleSelection(true),
+            Action::Group => Message::GroupSelection,
+            Action::Ungroup => Message::UngroupSelection,
+            Action::BringToFront => Message::BringToFront,
+            Action::SendToBack => Message::SendToBack,
+            Action::Duplicate => Message::DuplicateSelection,
+            Action::Copy => Message::CopySelection,
+            Action::Cut => Message::CutSelection,
+            Action::Paste => Message::Paste,
+            Action::Delete => Message::DeleteSelection,
+            Action::NudgeLeft => Message::NudgeSelection(Point::new(-NUDGE, 0.)),
+            Action::NudgeRight => Message::NudgeSelection(Point::new(NUDGE, 0.)),
+            Action::NudgeUp => Message::NudgeSelection(Point::new(0., -NUDGE)),
+            Action::NudgeDown => Message::NudgeSelection(Point::new(0., NUDGE)),
+            Action::NudgeLeftFar => Message::NudgeSelection(Point::new(-NUDGE_FAR, 0.)),
+            Action::NudgeRightFar => Message::NudgeSelection(Point::new(NUDGE_FAR, 0.)),
+            Action::NudgeUpFar => Message::NudgeSelection(Point::new(0., -NUDGE_FAR)),
+            Action::NudgeDownFar => Message::NudgeSelection(Point::new(0., NUDGE_FAR)),
+            Action::ShowShortcuts => Message::ToggleShortcutsOverlay,
+            Action::CommandPalette => Message::TogglePalette,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::SelectAll => "Select all",
+            Action::Deselect => "Deselect",
+            Action::NextLayer => "Select next layer",
+            Action::PreviousLayer => "Select previous layer",
+            Action::Group => "Group",
+            Action::Ungroup => "Ungroup",
+            Action::BringToFront => "Bring to front",
+            Action::SendToBack => "Send to back",
+            Action::Duplicate => "Duplicate",
+            Action::Copy => "Copy",
+            Action::Cut => "Cut",
+            Action::Paste => "Paste",
+            Action::Delete => "Delete",
+            Action::NudgeLeft => "Nudge left",
+            Action::NudgeRight => "Nudge right",
+            Action::NudgeUp => "Nudge up",
+            Action::NudgeDown => "Nudge down",
+            Action::NudgeLeftFar => "Nudge left 10 px",
+            Action::NudgeRightFar => "Nudge right 10 px",
+            Action::NudgeUpFar => "Nudge up 10 px",
+            Action::NudgeDownFar => "Nudge down 10 px",
+            Action::ShowShortcuts => "Show shortcuts",
+            Action::CommandPalette => "Command palette",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A key together with the modifiers that have to be held, written like "Ctrl+Shift+Z".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    /// Lowercase character or the name of a named key such as "ArrowLeft".
+    key: String,
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+}
+
+impl Chord {
+    pub fn from_event(key: &Key, modifiers: Modifiers) -> Option<Self> {
+        let key = match key.as_ref() {
+            Key::Character(character) => character.to_lowercase(),
+            Key::Named(named) => format!("{named:?}"),
+            Key::Unidentified => return None,
+        };
+
+        Some(Self {
+            key,
+            ctrl: modifiers.command(),
+            shift: modifiers.shift(),
+            alt: modifiers.alt(),
+        })
+    }
+
+    pub fn parse(chord: &str) -> Option<Self> {
+        let mut parsed = Self {
+            key: String::new(),
+            ctrl: false,
+            shift: false,
+            alt: false,
+        };
+
+        for part in chord.split('+').map(str::trim) {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "cmd" => parsed.ctrl = true,
+                "shift" => parsed.shift = true,
+                "alt" => parsed.alt = true,
+                _ if !parsed.key.is_empty() || part.is_empty() => return None,
+                _ if part.eq_ignore_ascii_case(PLUS_KEY) => parsed.key = "+".to_string(),
+                _ if part.chars().count() == 1 => parsed.key = part.to_lowercase(),
+                _ => parsed.key = part.to_string(),
+            }
+        }
+
+        (!parsed.key.is_empty()).then_some(parsed)
+    }
+
+    /// Modifier keys pressed on their own never make a chord.
+    pub fn is_modifier_only(&self) -> bool {
+        matches!(
+            self.key.as_str(),
+            "Control" | "Shift" | "Alt" | "Super" | "Meta"
+        )
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.key == "+" {
+            return write!(f, "{PLUS_KEY}");
+        }
+        let mut characters = self.key.chars();
+        match characters.next() {
+            Some(first) => write!(f, "{}{}", first.to_uppercase(), characters.as_str()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Key chords bound to every action, loaded from `keymap.json` in the config directory.
+///
+/// The file maps action names to lists of chords, actions missing from it keep their
+/// default bindings:
+///
+/// ```json
+/// { "duplicate": ["Ctrl+D"], "delete": ["Delete", "Backspace"] }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<Chord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Action::SelectAll, vec!["Ctrl+A"]),
+            (Action::Deselect, vec!["Escape"]),
+            (Action::NextLayer, vec!["Tab"]),
+            (Action::PreviousLayer, vec!["Shift+Tab"]),
+            (Action::Group, vec!["Ctrl+G"]),
+            (Action::Ungroup, vec!["Ctrl+Shift+G"]),
+            (Action::BringToFront, vec!["Ctrl+]"]),
+            (Action::SendToBack, vec!["Ctrl+["]),
+            (Action::Duplicate, vec!["Ctrl+D"]),
+            (Action::Copy, vec!["Ctrl+C"]),
+            (Action::Cut, vec!["Ctrl+X"]),
+            (Action::Paste, vec!["Ctrl+V"]),
+            (Action::Delete, vec!["Delete", "Backspace"]),
+            (Action::NudgeLeft, vec!["ArrowLeft"]),
+            (Action::NudgeRight, vec!["ArrowRight"]),
+            (Action::NudgeUp, vec!["ArrowUp"]),
+            (Action::NudgeDown, vec!["ArrowDown"]),
+            (Action::NudgeLeftFar, vec!["Shift+ArrowLeft"]),
+            (Action::NudgeRightFar, vec!["Shift+ArrowRight"]),
+            (Action::NudgeUpFar, vec!["Shift+ArrowUp"]),
+            (Action::NudgeDownFar, vec!["Shift+ArrowDown"]),
+            (Action::ShowShortcuts, vec!["F1"]),
+            (Action::CommandPalette, vec!["Ctrl+Shift+P"]),
+        ]
+        .into_iter()
+        .map(|(action, chords)| {
+            (
+                action,
+                chords.into_iter().filter_map(Chord::parse).collect(),
+            )
+        })
+        .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Reads the keymap file, falling back to the defaults when it is missing or invalid.
+    ///
+    /// Why an existing file was ignored is returned alongside.
+    pub fn load() -> (Self, Option<Error>) {
+        let mut keymap = Self::default();
+        let Some(contents) = keymap_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return (keymap, None);
+        };
+
+        match serde_json::from_str::<BTreeMap<Action, Vec<String>>>(&contents) {
+            Ok(bindings) => {
+                for (action, chords) in bindings {
+                    keymap.bindings.insert(
+                        action,
+                        chords
+                            .iter()
+                            .filter_map(|chord| Chord::parse(chord))
+                            .collect(),
+                    );
+                }
+                (keymap, None)
+            }
+            Err(error) => (
+                keymap,
+                Some(Error::Settings {
+                    file: KEYMAP_FILE,
+                    reason: error.to_string(),
+                }),
+            ),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = keymap_path() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let bindings: BTreeMap<Action, Vec<String>> = self
+            .bindings
+            .iter()
+            .map(|(action, chords)| (*action, chords.iter().map(Chord::to_string).collect()))
+            .collect();
+        let json = serde_json::to_string_pretty(&bindings).map_err(std::io::Error::other)?;
+
+        fs::write(path, json)
+    }
+
+    pub fn action_for(&self, chord: &Chord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, chords)| chords.contains(chord))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn chords(&self, action: Action) -> &[Chord] {
+        self.bindings
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Other actions already bound to `chord`.
+    pub fn conflicts(&self, action: Action, chord: &Chord) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(other, chords)| **other != action && chords.contains(chord))
+            .map(|(other, _)| *other)
+            .collect()
+    }
+
+    /// Adds `chord` to the chords of `action`, removing it from any other action.
+    pub fn bind(&mut self, action: Action, chord: Chord) {
+        for chords in self.bindings.values_mut() {
+            chords.retain(|bound| *bound != chord);
+        }
+        self.bindings.entry(action).or_default().push(chord);
+    }
+
+    /// Removes `chord` from `action`, leaving its other chords bound.
+    pub fn unbind(&mut self, action: Action, chord: &Chord) {
+        if let Some(chords) = self.bindings.get_mut(&action) {
+            chords.retain(|bound| bound != chord);
+        }
+    }
+}
+
+fn keymap_path() -> Option<PathBuf> {
+    utils::config_dir().map(|dir| dir.join(KEYMAP_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_round_trip_through_their_text() {
+        for text in [
+            "Ctrl+Shift+Z",
+            "Delete",
+            "Shift+ArrowLeft",
+            "Ctrl+]",
+            "Alt+F1",
+            "Plus",
+            "Ctrl+Plus",
+        ] {
+            let chord = Chord::parse(text).expect(text);
+            assert_eq!(chord.to_string(), text);
+            assert_eq!(Chord::parse(&chord.to_string()), Some(chord));
+        }
+    }
+
+    #[test]
+    fn plus_key_is_written_by_name() {
+        let chord = Chord {
+            key: "+".to_string(),
+            ctrl: true,
+            shift: false,
+            alt: false,
+        };
+        assert_eq!(chord.to_string(), "Ctrl+Plus");
+        assert_eq!(Chord::parse("ctrl+plus"), Some(chord));
+    }
+
+    #[test]
+    fn parse_rejects_missing_and_doubled_keys() {
+        assert_eq!(Chord::parse(""), None);
+        assert_eq!(Chord::parse("Ctrl+"), None);
+        assert_eq!(Chord::parse("Ctrl++"), None);
+        assert_eq!(Chord::parse("A+B"), None);
+        assert_eq!(Chord::parse("Shift"), None);
+    }
+
+    #[test]
+    fn binding_keeps_other_chords_of_the_action() {
+        let mut keymap = Keymap::default();
+        let chord = Chord::parse("Ctrl+K").unwrap();
+        keymap.bind(Action::Delete, chord.clone());
+        assert_eq!(
+            keymap.chords(Action::Delete),
+            [
+                Chord::parse("Delete").unwrap(),
+                Chord::parse("Backspace").unwrap(),
+                chord.clone(),
+            ]
+        );
+        assert_eq!(keymap.action_for(&chord), Some(Action::Delete));
+
+        keymap.unbind(Action::Delete, &Chord::parse("Backspace").unwrap());
+        assert_eq!(
+            keymap.chords(Action::Delete),
+            [Chord::parse("Delete").unwrap(), chord]
+        );
+    }
+
+    #[test]
+    fn default_bindings_all_parse() {
+        let keymap = Keymap::default();
+        assert!(
+            Action::ALL
+                .iter()
+                .all(|action| !keymap.chords(*action).is_empty())
+        );
+    }
+}
@@ -1,12 +1,55 @@
-use iced::{Point, Rectangle, widget::canvas::Frame};
+use iced::{Point, Rectangle, Size, widget::canvas::Frame};
+
+use crate::{
+    error::Error,
+    id::{Id, IdGenerator},
+    layer_handler::LayerHandler,
+    project::LayerData,
+};
+
+/// Edges and center lines of a layer, the same lines snapping aligns against.
+#[derive(Debug, Clone, Copy)]
+pub struct Edges {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    pub center_x: f32,
+    pub center_y: f32,
+}
+
+impl Edges {
+    pub fn of(rect: &Rectangle) -> Self {
+        Self {
+            left: rect.x,
+            right: rect.x + rect.width,
+            top: rect.y,
+            bottom: rect.y + rect.height,
+            center_x: rect.x + rect.width * 0.5,
+            center_y: rect.y + rect.height * 0.5,
+        }
+    }
 
-use crate::{id::Id, layer_handler::LayerHandler};
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.bottom - self.top
+    }
+}
 
 pub struct Layer {
     pub id: Id,
     pub name: String,
     pub handler: Box<dyn LayerHandler>,
     pub is_selected: bool,
+    pub importance: f32,
+    pub hidden: bool,
+    pub locked: bool,
+    pub opacity: f32,
+    /// Whether a group shows its children in the layer list.
+    pub expanded: bool,
 }
 
 impl Layer {
@@ -16,6 +59,11 @@ impl Layer {
             name,
             handler,
             is_selected: false,
+            importance: 1.,
+            hidden: false,
+            locked: false,
+            opacity: 1.,
+            expanded: true,
         }
     }
 
@@ -23,116 +71,128 @@ impl Layer {
         &self.name
     }
 
-    pub fn draw(&self, frame: &mut Frame) {
-        self.handler.draw(frame);
+    pub fn to_data(&self) -> Result<LayerData, Error> {
+        Ok(LayerData {
+            name: self.name.clone(),
+            importance: self.importance,
+            hidden: self.hidden,
+            locked: self.locked,
+            opacity: self.opacity,
+            expanded: self.expanded,
+            content: self.handler.to_data()?,
+        })
     }
 
-    pub fn move_by(&mut self, delta: Point) {
-        let mut rect = self.handler.get_rect();
-        rect.x += delta.x;
-        rect.y += delta.y;
-        self.handler.set_rect(rect);
+    /// Unselected copy under a new id, sharing decoded pixels instead of serializing them
+    /// like [`Layer::to_data`].
+    pub fn duplicate(&self, ids: &mut IdGenerator) -> Layer {
+        Layer {
+            id: ids.generate(),
+            name: self.name.clone(),
+            handler: self.handler.duplicate(ids),
+            is_selected: false,
+            importance: self.importance,
+            hidden: self.hidden,
+            locked: self.locked,
+            opacity: self.opacity,
+            expanded: self.expanded,
+        }
     }
 
-    pub fn move_by_snap(
-        &mut self,
-        delta: Point,
-        layers: &Vec<&Layer>,
-        bounds: &Rectangle,
-    ) -> (Point, Point) {
-        let mut rect = self.handler.get_rect();
+    pub fn edges(&self) -> Edges {
+        Edges::of(&self.handler.get_rect())
+    }
 
-        let orig_x = rect.x;
-        let orig_y = rect.y;
+    /// Whether `point` is on the layer as drawn, rotated around the center of its rect.
+    pub fn contains(&self, point: Point) -> bool {
+        self.handler.get_rect().contains(self.unrotate(point))
+    }
 
-        rect.x += delta.x;
-        rect.y += delta.y;
+    /// Whether any part of the layer as drawn overlaps `area`.
+    pub fn intersects(&self, area: &Rectangle) -> bool {
+        let rect = self.handler.get_rect();
+        let rotation = self.handler.get_rotation().unwrap_or(0.);
+        if rotation == 0. {
+            return area.intersects(&rect);
+        }
 
-        let mut snap_point = Point::new(-1., -1.);
+        // Separating axis test, along the axes of the area and then of the rotated rect.
+        let center = rect.center();
+        let drawn = corners(&rect).map(|corner| rotate_around(corner, center, rotation));
+        let unrotated_area = corners(area).map(|corner| self.unrotate(corner));
 
-        const SNAP_DISTANCE: f32 = 3.0;
+        area.intersects(&bounding_box(&drawn)) && rect.intersects(&bounding_box(&unrotated_area))
+    }
 
-        if (rect.x - bounds.x).abs() < SNAP_DISTANCE {
-            rect.x = bounds.x;
-            snap_point.x = 0.;
-        }
-        if (rect.y - bounds.y).abs() < SNAP_DISTANCE {
-            rect.y = bounds.y;
-            snap_point.y = 0.;
-        }
-        if ((rect.x + rect.width) - (bounds.x + bounds.width)).abs() < SNAP_DISTANCE {
-            rect.x = bounds.x + bounds.width - rect.width;
-            snap_point.x = 1.0;
-        }
-        if ((rect.y + rect.height) - (bounds.y + bounds.height)).abs() < SNAP_DISTANCE {
-            rect.y = bounds.y + bounds.height - rect.height;
-            snap_point.y = 1.0;
+    /// `point` turned back by the layer's rotation, into the space of its rect.
+    fn unrotate(&self, point: Point) -> Point {
+        match self.handler.get_rotation() {
+            Some(rotation) if rotation != 0. => {
+                rotate_around(point, self.handler.get_rect().center(), -rotation)
+            }
+            _ => point,
         }
+    }
 
-        let center_x = rect.x + rect.width * 0.5;
-        let center_y = rect.y + rect.height * 0.5;
+    pub fn draw(&self, frame: &mut Frame, opacity: f32) {
+        if self.hidden {
+            return;
+        }
 
-        for &layer in layers {
-            let other = layer.handler.get_rect();
+        self.handler.draw(frame, opacity * self.opacity);
+    }
 
-            let other_center_x = other.x + other.width * 0.5;
-            let other_center_y = other.y + other.height * 0.5;
+    /// Hidden and locked layers can't be picked on the canvas.
+    pub fn is_selectable(&self) -> bool {
+        !self.hidden && !self.locked
+    }
 
-            if (center_x - other_center_x).abs() < SNAP_DISTANCE {
-                rect.x = other_center_x - rect.width * 0.5;
-                snap_point.x = 0.5;
-            }
+    pub fn is_group(&self) -> bool {
+        self.handler.children().is_some()
+    }
 
-            if (center_y - other_center_y).abs() < SNAP_DISTANCE {
-                rect.y = other_center_y - rect.height * 0.5;
-                snap_point.y = 0.5;
-            }
+    /// Finds this layer or one nested inside it.
+    pub fn find(&self, id: Id) -> Option<&Layer> {
+        if self.id == id {
+            return Some(self);
+        }
 
-            let left = rect.x;
-            let right = rect.x + rect.width;
-            let other_left = other.x;
-            let other_right = other.x + other.width;
+        self.handler
+            .children()?
+            .iter()
+            .find_map(|child| child.find(id))
+    }
 
-            if (left - other_right).abs() < SNAP_DISTANCE {
-                rect.x = other_right;
-                snap_point.x = 0.;
-            } else if (left - other_left).abs() < SNAP_DISTANCE {
-                rect.x = other_left;
-                snap_point.x = 0.;
-            } else if (right - other_left).abs() < SNAP_DISTANCE {
-                rect.x = other_left - rect.width;
-                snap_point.x = 1.0;
-            } else if (right - other_right).abs() < SNAP_DISTANCE {
-                rect.x = other_right - rect.width;
-                snap_point.x = 1.0;
-            }
+    /// Finds this layer or one nested inside it.
+    pub fn find_mut(&mut self, id: Id) -> Option<&mut Layer> {
+        if self.id == id {
+            return Some(self);
+        }
 
-            let top = rect.y;
-            let bottom = rect.y + rect.height;
-            let other_top = other.y;
-            let other_bottom = other.y + other.height;
+        self.handler
+            .children_mut()?
+            .iter_mut()
+            .find_map(|child| child.find_mut(id))
+    }
 
-            if (top - other_bottom).abs() < SNAP_DISTANCE {
-                rect.y = other_bottom;
-                snap_point.y = 0.;
-            } else if (top - other_top).abs() < SNAP_DISTANCE {
-                rect.y = other_top;
-                snap_point.y = 0.;
-            } else if (bottom - other_top).abs() < SNAP_DISTANCE {
-                rect.y = other_top - rect.height;
-                snap_point.y = 1.0;
-            } else if (bottom - other_bottom).abs() < SNAP_DISTANCE {
-                rect.y = other_bottom - rect.height;
-                snap_point.y = 1.0;
-            }
-        }
+    pub fn move_by(&mut self, delta: Point) {
+        let mut rect = self.handler.get_rect();
+        rect.x += delta.x;
+        rect.y += delta.y;
+        self.handler.set_rect(rect);
+    }
 
+    pub fn move_by_snap(
+        &mut self,
+        delta: Point,
+        layers: &Vec<&Layer>,
+        bounds: &Rectangle,
+    ) -> (Point, Point) {
+        let (rect, ignored_delta, snap_point) =
+            snap_move(self.handler.get_rect(), delta, layers, bounds);
         self.handler.set_rect(rect);
 
-        (
-            Point::new(delta.x - (rect.x - orig_x), delta.y - (rect.y - orig_y)),
-            snap_point,
-        )
+        (ignored_delta, snap_point)
     }
 
     pub fn on_select(&mut self) {
@@ -146,47 +206,7 @@ impl Layer {
     }
 
     pub fn resize_by(&mut self, delta: Point, pivot: Point, preserve_aspect: bool) {
-        let mut rect = self.handler.get_rect();
-        let width = rect.width;
-        let height = rect.height;
-        let aspect = width / height;
-
-        let effective_delta_x = delta.x * (1.0 - 2.0 * pivot.x);
-        let effective_delta_y = delta.y * (1.0 - 2.0 * pivot.y);
-
-        let mut new_width: f32;
-        let mut new_height: f32;
-
-        if preserve_aspect {
-            if pivot.x == 0.0 || pivot.x == 1.0 {
-                new_width = width + effective_delta_x;
-                new_height = new_width / aspect;
-            } else if pivot.y == 0.0 || pivot.y == 1.0 {
-                new_height = height + effective_delta_y;
-                new_width = new_height * aspect;
-            } else {
-                if effective_delta_x.abs() > effective_delta_y.abs() {
-                    new_width = width + effective_delta_x;
-                    new_height = new_width / aspect;
-                } else {
-                    new_height = height + effective_delta_y;
-                    new_width = new_height * aspect;
-                }
-            }
-        } else {
-            new_width = width + effective_delta_x;
-            new_height = height + effective_delta_y
-        };
-
-        new_width = new_width.max(16.);
-        new_height = new_height.max(16.);
-
-        rect.x -= (new_width - width) * pivot.x;
-        rect.y -= (new_height - height) * pivot.y;
-
-        rect.width = new_width;
-        rect.height = new_height;
-
+        let rect = resized_rect(self.handler.get_rect(), delta, pivot, preserve_aspect);
         self.handler.set_rect(rect);
     }
 
@@ -378,3 +398,181 @@ impl Layer {
         (ignored_delta, snap_point)
     }
 }
+
+/// Moves `rect` by `delta` and snaps it to the bounds and to the edges of `layers`.
+///
+/// Returns the snapped rectangle, the part of `delta` swallowed by snapping and the
+/// snapped point relative to the rectangle (negative when not snapped).
+pub fn snap_move(
+    mut rect: Rectangle,
+    delta: Point,
+    layers: &Vec<&Layer>,
+    bounds: &Rectangle,
+) -> (Rectangle, Point, Point) {
+    let orig_x = rect.x;
+    let orig_y = rect.y;
+
+    rect.x += delta.x;
+    rect.y += delta.y;
+
+    let mut snap_point = Point::new(-1., -1.);
+
+    const SNAP_DISTANCE: f32 = 3.0;
+
+    if (rect.x - bounds.x).abs() < SNAP_DISTANCE {
+        rect.x = bounds.x;
+        snap_point.x = 0.;
+    }
+    if (rect.y - bounds.y).abs() < SNAP_DISTANCE {
+        rect.y = bounds.y;
+        snap_point.y = 0.;
+    }
+    if ((rect.x + rect.width) - (bounds.x + bounds.width)).abs() < SNAP_DISTANCE {
+        rect.x = bounds.x + bounds.width - rect.width;
+        snap_point.x = 1.0;
+    }
+    if ((rect.y + rect.height) - (bounds.y + bounds.height)).abs() < SNAP_DISTANCE {
+        rect.y = bounds.y + bounds.height - rect.height;
+        snap_point.y = 1.0;
+    }
+
+    let center_x = rect.x + rect.width * 0.5;
+    let center_y = rect.y + rect.height * 0.5;
+
+    for &layer in layers {
+        let other = layer.edges();
+
+        if (center_x - other.center_x).abs() < SNAP_DISTANCE {
+            rect.x = other.center_x - rect.width * 0.5;
+            snap_point.x = 0.5;
+        }
+
+        if (center_y - other.center_y).abs() < SNAP_DISTANCE {
+            rect.y = other.center_y - rect.height * 0.5;
+            snap_point.y = 0.5;
+        }
+
+        let left = rect.x;
+        let right = rect.x + rect.width;
+
+        if (left - other.right).abs() < SNAP_DISTANCE {
+            rect.x = other.right;
+            snap_point.x = 0.;
+        } else if (left - other.left).abs() < SNAP_DISTANCE {
+            rect.x = other.left;
+            snap_point.x = 0.;
+        } else if (right - other.left).abs() < SNAP_DISTANCE {
+            rect.x = other.left - rect.width;
+            snap_point.x = 1.0;
+        } else if (right - other.right).abs() < SNAP_DISTANCE {
+            rect.x = other.right - rect.width;
+            snap_point.x = 1.0;
+        }
+
+        let top = rect.y;
+        let bottom = rect.y + rect.height;
+
+        if (top - other.bottom).abs() < SNAP_DISTANCE {
+            rect.y = other.bottom;
+            snap_point.y = 0.;
+        } else if (top - other.top).abs() < SNAP_DISTANCE {
+            rect.y = other.top;
+            snap_point.y = 0.;
+        } else if (bottom - other.top).abs() < SNAP_DISTANCE {
+            rect.y = other.top - rect.height;
+            snap_point.y = 1.0;
+        } else if (bottom - other.bottom).abs() < SNAP_DISTANCE {
+            rect.y = other.bottom - rect.height;
+            snap_point.y = 1.0;
+        }
+    }
+
+    (
+        rect,
+        Point::new(delta.x - (rect.x - orig_x), delta.y - (rect.y - orig_y)),
+        snap_point,
+    )
+}
+
+/// Resizes `rect` by dragging the handle opposite to `pivot` by `delta`.
+pub fn resized_rect(
+    mut rect: Rectangle,
+    delta: Point,
+    pivot: Point,
+    preserve_aspect: bool,
+) -> Rectangle {
+    let width = rect.width;
+    let height = rect.height;
+    let aspect = width / height;
+
+    let effective_delta_x = delta.x * (1.0 - 2.0 * pivot.x);
+    let effective_delta_y = delta.y * (1.0 - 2.0 * pivot.y);
+
+    let mut new_width: f32;
+    let mut new_height: f32;
+
+    if preserve_aspect {
+        if pivot.x == 0.0 || pivot.x == 1.0 {
+            new_width = width + effective_delta_x;
+            new_height = new_width / aspect;
+        } else if pivot.y == 0.0 || pivot.y == 1.0 {
+            new_height = height + effective_delta_y;
+            new_width = new_height * aspect;
+        } else {
+            if effective_delta_x.abs() > effective_delta_y.abs() {
+                new_width = width + effective_delta_x;
+                new_height = new_width / aspect;
+            } else {
+                new_height = height + effective_delta_y;
+                new_width = new_height * aspect;
+            }
+        }
+    } else {
+        new_width = width + effective_delta_x;
+        new_height = height + effective_delta_y
+    };
+
+    new_width = new_width.max(16.);
+    new_height = new_height.max(16.);
+
+    rect.x -= (new_width - width) * pivot.x;
+    rect.y -= (new_height - height) * pivot.y;
+
+    rect.width = new_width;
+    rect.height = new_height;
+
+    rect
+}
+
+fn rotate_around(point: Point, center: Point, angle: f32) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    let (x, y) = (point.x - center.x, point.y - center.y);
+    Point::new(center.x + x * cos - y * sin, center.y + x * sin + y * cos)
+}
+
+fn corners(rect: &Rectangle) -> [Point; 4] {
+    [
+        Point::new(rect.x, rect.y),
+        Point::new(rect.x + rect.width, rect.y),
+        Point::new(rect.x + rect.width, rect.y + rect.height),
+        Point::new(rect.x, rect.y + rect.height),
+    ]
+}
+
+fn bounding_box(points: &[Point; 4]) -> Rectangle {
+    let (min_x, max_x) = points
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), point| {
+            (min.min(point.x), max.max(point.x))
+        });
+    let (min_y, max_y) = points
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), point| {
+            (min.min(point.y), max.max(point.y))
+        });
+
+    Rectangle::new(
+        Point::new(min_x, min_y),
+        Size::new(max_x - min_x, max_y - min_y),
+    )
+}
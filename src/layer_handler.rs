@@ -1,61 +1,459 @@
-use std::path::PathBuf;
+use std::{fs, path::PathBuf, sync::Arc, time::SystemTime};
 
 use iced::{
-    Color, Element, Point, Rectangle, Size,
+    Color, Element, Point, Radians, Rectangle, Size, Vector,
     advanced::{
         graphics::geometry::{Path, Style},
         image::Handle,
+        text::Alignment,
     },
-    widget::canvas::{Frame, Stroke},
+    alignment,
+    widget::{
+        canvas::{self, Frame, Image, Stroke},
+        container, text,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arrange::Axis,
+    bg_maker::Message,
+    decode::{self, LoadRequest, Preview},
+    error::Error,
+    id::IdGenerator,
+    layer::Layer,
+    layout,
+    project::{self, ContentData, SourceData},
 };
 
-use crate::bg_maker::Message;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Fit {
+    Stretch,
+    Cover,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Decoration {
+    pub polaroid: bool,
+    pub shadow: bool,
+}
 
 pub trait LayerHandler: 'static {
     fn get_preview(&self) -> Element<Message>;
-    fn draw(&self, frame: &mut Frame);
+    fn draw(&self, frame: &mut Frame, opacity: f32);
     fn get_rect(&self) -> Rectangle;
     fn set_rect(&mut self, rect: Rectangle);
+    fn get_native_size(&self) -> Size;
+    fn set_fit(&mut self, fit: Fit);
+    fn set_corner_radius(&mut self, radius: f32);
+    fn set_rotation(&mut self, rotation: f32);
+    fn set_decoration(&mut self, decoration: Decoration);
     fn on_select(&mut self);
     fn on_deselect(&mut self);
+    fn to_data(&self) -> Result<ContentData, Error>;
+
+    /// Copy of the content sharing its decoded pixels, nested layers get ids from `ids`.
+    fn duplicate(&self, ids: &mut IdGenerator) -> Box<dyn LayerHandler>;
+
+    /// Mirrors the layer's content along `axis`.
+    fn flip(&mut self, axis: Axis);
+
+    /// File the layer's content was loaded from.
+    fn source_path(&self) -> Option<&PathBuf> {
+        None
+    }
+
+    /// Swaps the content for another file, keeping the rect and every other setting.
+    fn set_source(&mut self, _path: PathBuf) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Rotation in radians, `None` for layers that can't be rotated as a whole.
+    fn get_rotation(&self) -> Option<f32> {
+        None
+    }
+
+    /// Number of animation frames the content can be shown at.
+    fn frame_count(&self) -> usize {
+        1
+    }
+
+    /// Index of the animation frame that is shown.
+    fn frame(&self) -> usize {
+        0
+    }
+
+    fn set_frame(&mut self, _frame: usize) {}
+
+    /// Whether the content is still being decoded and drawn as a placeholder.
+    fn is_loading(&self) -> bool {
+        false
+    }
+
+    /// File, frame and raster size that have to be decoded in the background, returned
+    /// only once.
+    fn take_pending_load(&mut self) -> Option<LoadRequest> {
+        None
+    }
+
+    /// Whether `generation` is the latest load requested, older results belong to a
+    /// previous file or frame.
+    fn is_current_load(&self, _generation: u64) -> bool {
+        false
+    }
+
+    /// Shows a preview decoded in the background.
+    fn set_preview(&mut self, _preview: Preview) {}
+
+    /// Checks the source file after it changed on disk, marking the layer missing when the
+    /// file is gone and queueing a reload when it was modified or came back.
+    fn refresh_source(&mut self) {}
+
+    /// Whether the source file can't be found, the layer is drawn as a placeholder then.
+    fn is_missing(&self) -> bool {
+        false
+    }
+
+    /// File and frame to decode at full resolution for an export, `None` when the drawn
+    /// pixels are already sharp enough, like for SVG files and pasted images.
+    fn full_resolution_load(&self) -> Option<LoadRequest> {
+        None
+    }
+
+    /// Draws the full resolution pixels decoded for an export, or the preview again when
+    /// `None`.
+    fn set_full_resolution(&mut self, _pixels: Option<Arc<image::RgbaImage>>) {}
+
+    fn children(&self) -> Option<&Vec<Layer>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<&mut Vec<Layer>> {
+        None
+    }
+}
+
+/// Where the pixels of an image layer come from.
+#[derive(Clone)]
+pub enum ImageSource {
+    File(PathBuf),
+    /// Decoded pixels without a file, e.g. pasted from the clipboard.
+    Memory(Arc<image::RgbaImage>),
 }
 
+#[derive(Clone)]
 pub struct ImageLayer {
-    handle: Handle,
+    source: ImageSource,
+    /// `None` while the file is still being decoded.
+    handle: Option<Handle>,
+    /// Screen sized copy of a file's pixels, the handle is built from it outside of exports.
+    preview: Option<Arc<image::RgbaImage>>,
+    /// Cached thumbnail of the file, kept to mirror it again when the layer is flipped.
+    thumbnail_pixels: Option<Arc<image::RgbaImage>>,
+    /// Thumbnail shown in the layer panel, mirrored like the layer.
+    thumbnail: Option<Handle>,
+    /// A preview has to be decoded for the current source or frame.
+    load_pending: bool,
+    /// Number of the latest load, see [`LoadRequest::generation`].
+    load_generation: u64,
+    /// Full resolution pixels of the file the handle is built from during an export.
+    full_resolution: Option<Arc<image::RgbaImage>>,
+    /// Modification time of the file when it was last decoded.
+    modified: Option<SystemTime>,
+    missing: bool,
     rect: Rectangle,
+    native_size: Size,
+    fit: Fit,
+    corner_radius: f32,
+    rotation: f32,
+    decoration: Decoration,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    /// Shown frame of an animated GIF or APNG.
+    frame: usize,
+    /// Counted on the first load of a file, so other frames don't decode them all again.
+    frame_count: Option<usize>,
+    /// Pixel size of the SVG raster the handle was built from, used to render it again
+    /// when the layer is scaled far past it.
+    rasterized_size: (u32, u32),
     is_selected: bool,
 }
 
 impl ImageLayer {
-    pub fn new(image_path: PathBuf) -> Self {
-        let handle = Handle::from_path(&image_path);
-        let dimensions_result = image::image_dimensions(&image_path);
-        let (width, height) = dimensions_result.unwrap();
+    /// Pixels rendered per canvas pixel for SVG files, matching the export density.
+    const VECTOR_DENSITY: f32 = crate::maker_canvas::EXPORT_SCALE_FACTOR;
+
+    /// Creates a layer for a file that is drawn as a placeholder covering `rect` until
+    /// its preview is decoded.
+    pub fn new(image_path: PathBuf, rect: Rectangle) -> Self {
+        Self {
+            source: ImageSource::File(image_path),
+            handle: None,
+            preview: None,
+            thumbnail_pixels: None,
+            thumbnail: None,
+            load_pending: true,
+            load_generation: 0,
+            full_resolution: None,
+            modified: None,
+            missing: false,
+            rect,
+            native_size: rect.size(),
+            fit: Fit::Stretch,
+            corner_radius: 0.,
+            rotation: 0.,
+            decoration: Decoration::default(),
+            flip_horizontal: false,
+            flip_vertical: false,
+            frame: 0,
+            frame_count: None,
+            rasterized_size: (0, 0),
+            is_selected: false,
+        }
+    }
+
+    /// Creates a layer from decoded pixels that have no file behind them.
+    pub fn from_pixels(pixels: image::RgbaImage) -> Self {
         let rect = Rectangle {
             x: 0.,
             y: 0.,
-            width: width as f32,
-            height: height as f32,
+            width: pixels.width() as f32,
+            height: pixels.height() as f32,
         };
 
-        Self {
-            handle,
+        let mut layer = Self {
+            source: ImageSource::Memory(Arc::new(pixels)),
+            handle: None,
+            preview: None,
+            thumbnail_pixels: None,
+            thumbnail: None,
+            load_pending: false,
+            load_generation: 0,
+            full_resolution: None,
+            modified: None,
+            missing: false,
             rect,
+            native_size: rect.size(),
+            fit: Fit::Stretch,
+            corner_radius: 0.,
+            rotation: 0.,
+            decoration: Decoration::default(),
+            flip_horizontal: false,
+            flip_vertical: false,
+            frame: 0,
+            frame_count: None,
+            rasterized_size: (0, 0),
             is_selected: false,
+        };
+        layer.reload_handle();
+        layer
+    }
+
+    /// Reloads the image handle, flipped images are mirrored up front since the canvas
+    /// can't draw with a negative scale.
+    fn reload_handle(&mut self) {
+        let pixels = match &self.source {
+            ImageSource::File(_) => self.full_resolution.as_ref().or(self.preview.as_ref()),
+            ImageSource::Memory(pixels) => Some(pixels),
+        };
+        let Some(pixels) = pixels else {
+            self.handle = None;
+            return;
+        };
+
+        let mut image = image::DynamicImage::ImageRgba8((**pixels).clone());
+        if self.flip_horizontal {
+            image = image.fliph();
         }
+        if self.flip_vertical {
+            image = image.flipv();
+        }
+        let rgba = image.into_rgba8();
+        self.handle = Some(Handle::from_rgba(
+            rgba.width(),
+            rgba.height(),
+            rgba.into_raw(),
+        ));
+    }
+
+    /// Rebuilds the layer panel thumbnail, mirrored the same way as the handle.
+    fn reload_thumbnail(&mut self) {
+        self.thumbnail = self.thumbnail_pixels.as_deref().map(|pixels| {
+            let mut pixels = pixels.clone();
+            if self.flip_horizontal {
+                image::imageops::flip_horizontal_in_place(&mut pixels);
+            }
+            if self.flip_vertical {
+                image::imageops::flip_vertical_in_place(&mut pixels);
+            }
+            Handle::from_rgba(pixels.width(), pixels.height(), pixels.into_raw())
+        });
+    }
+
+    /// Size the image is drawn at, larger than the rect when it is cropped to cover it.
+    fn drawn_size(&self) -> Size {
+        match self.fit {
+            Fit::Stretch => self.rect.size(),
+            Fit::Cover => layout::cover_rect(self.native_size, self.rect).size(),
+        }
+    }
+
+    /// Raster size an SVG file is rendered at for the size it is drawn at.
+    fn raster_target(&self) -> (u32, u32) {
+        let size = self.drawn_size();
+        decode::raster_size(
+            size.width * Self::VECTOR_DENSITY,
+            size.height * Self::VECTOR_DENSITY,
+        )
+    }
+
+    /// SVG rasters are rendered again when the layer grows past them or shrinks to a
+    /// quarter of them.
+    fn raster_outdated(&self) -> bool {
+        let ImageSource::File(path) = &self.source else {
+            return false;
+        };
+        if !decode::is_vector(path) || self.preview.is_none() {
+            return false;
+        }
+        let (width, height) = self.raster_target();
+        let (raster_width, raster_height) = self.rasterized_size;
+
+        width > raster_width
+            || height > raster_height
+            || width * 4 <= raster_width
+            || height * 4 <= raster_height
+    }
+
+    fn draw_clipped(&self, frame: &mut Frame, handle: &Handle, opacity: f32) {
+        let image = Image {
+            border_radius: self.corner_radius.into(),
+            ..Image::new(handle).opacity(opacity)
+        };
+        let image_rect = match self.fit {
+            Fit::Stretch => self.rect,
+            Fit::Cover => layout::cover_rect(self.native_size, self.rect),
+        };
+
+        frame.with_clip(self.rect, |clipping_frame| {
+            clipping_frame.draw_image(
+                Rectangle {
+                    x: image_rect.x - self.rect.x,
+                    y: image_rect.y - self.rect.y,
+                    ..image_rect
+                },
+                image,
+            );
+        });
+    }
+
+    /// Stands in for the image while it is being decoded or when its file is missing.
+    fn draw_placeholder(&self, frame: &mut Frame, opacity: f32) {
+        let (label, color) = if self.missing {
+            ("Missing", Color::from_rgba(0.8, 0.2, 0.2, 0.4 * opacity))
+        } else {
+            ("Loading…", Color::from_rgba(0.5, 0.5, 0.5, 0.4 * opacity))
+        };
+        let center = self.rect.center();
+        frame.with_save(|frame| {
+            frame.translate(Vector::new(center.x, center.y));
+            frame.rotate(self.rotation);
+            frame.fill(
+                &Path::rectangle(
+                    Point::new(-self.rect.width * 0.5, -self.rect.height * 0.5),
+                    self.rect.size(),
+                ),
+                color,
+            );
+            frame.fill_text(canvas::Text {
+                content: label.to_string(),
+                color: Color::from_rgba(1., 1., 1., opacity),
+                align_x: Alignment::Center,
+                align_y: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        });
+    }
+
+    fn draw_decoration(&self, frame: &mut Frame, opacity: f32) {
+        const BORDER: f32 = 0.04;
+        const BOTTOM_BORDER: f32 = 0.16;
+        const SHADOW_OFFSET: f32 = 6.;
+
+        let short_side = self.rect.width.min(self.rect.height);
+        let (border, bottom) = if self.decoration.polaroid {
+            (short_side * BORDER, short_side * BOTTOM_BORDER)
+        } else {
+            (0., 0.)
+        };
+        let center = self.rect.center();
+        let card = Path::rectangle(
+            Point::new(
+                -self.rect.width * 0.5 - border,
+                -self.rect.height * 0.5 - border,
+            ),
+            Size::new(
+                self.rect.width + border * 2.,
+                self.rect.height + border + bottom,
+            ),
+        );
+
+        frame.with_save(|frame| {
+            frame.translate(Vector::new(center.x, center.y));
+            frame.rotate(self.rotation);
+
+            if self.decoration.shadow {
+                frame.with_save(|frame| {
+                    frame.translate(Vector::new(SHADOW_OFFSET, SHADOW_OFFSET));
+                    frame.fill(&card, Color::from_rgba(0., 0., 0., 0.35 * opacity));
+                });
+            }
+
+            if self.decoration.polaroid {
+                frame.fill(&card, Color::from_rgba8(250, 250, 245, opacity));
+            }
+        });
     }
 }
 
 impl LayerHandler for ImageLayer {
     fn get_preview(&self) -> Element<Message> {
-        iced::widget::image(&self.handle)
-            .width(32)
-            .height(32)
-            .into()
+        match self.thumbnail.as_ref().or(self.handle.as_ref()) {
+            _ if self.missing => container(text("!").size(14))
+                .center_x(32)
+                .center_y(32)
+                .style(container::danger)
+                .into(),
+            Some(handle) => iced::widget::image(handle).width(32).height(32).into(),
+            None => container(text("…").size(14))
+                .center_x(32)
+                .center_y(32)
+                .style(container::bordered_box)
+                .into(),
+        }
     }
 
-    fn draw(&self, frame: &mut Frame) {
-        frame.draw_image(self.rect, &self.handle);
+    fn draw(&self, frame: &mut Frame, opacity: f32) {
+        if self.decoration.polaroid || self.decoration.shadow {
+            self.draw_decoration(frame, opacity);
+        }
+
+        match &self.handle {
+            _ if self.missing => self.draw_placeholder(frame, opacity),
+            None => self.draw_placeholder(frame, opacity),
+            Some(handle) if self.rotation != 0. => {
+                // Clipping can't follow the rotation, so rotated images are drawn whole.
+                frame.draw_image(
+                    self.rect,
+                    Image::new(handle)
+                        .rotation(Radians(self.rotation))
+                        .opacity(opacity),
+                );
+            }
+            Some(handle) => self.draw_clipped(frame, handle, opacity),
+        }
+
         if self.is_selected {
             let path = Path::rectangle(
                 Point {
@@ -85,6 +483,306 @@ impl LayerHandler for ImageLayer {
 
     fn set_rect(&mut self, rect: Rectangle) {
         self.rect = rect;
+        if self.raster_outdated() {
+            self.load_pending = true;
+        }
+    }
+
+    fn get_native_size(&self) -> Size {
+        self.native_size
+    }
+
+    fn set_fit(&mut self, fit: Fit) {
+        self.fit = fit;
+        if self.raster_outdated() {
+            self.load_pending = true;
+        }
+    }
+
+    fn set_corner_radius(&mut self, radius: f32) {
+        self.corner_radius = radius;
+    }
+
+    fn flip(&mut self, axis: Axis) {
+        match axis {
+            Axis::Horizontal => self.flip_horizontal = !self.flip_horizontal,
+            Axis::Vertical => self.flip_vertical = !self.flip_vertical,
+        }
+        self.reload_handle();
+        self.reload_thumbnail();
+    }
+
+    fn source_path(&self) -> Option<&PathBuf> {
+        match &self.source {
+            ImageSource::File(path) => Some(path),
+            ImageSource::Memory(_) => None,
+        }
+    }
+
+    fn set_source(&mut self, path: PathBuf) -> Result<(), Error> {
+        // Only the header is read here, the pixels replace the old ones once decoded.
+        decode::dimensions(&path)?;
+        self.source = ImageSource::File(path);
+        self.frame = 0;
+        self.frame_count = None;
+        self.load_pending = true;
+        self.missing = false;
+        Ok(())
+    }
+
+    fn get_rotation(&self) -> Option<f32> {
+        Some(self.rotation)
+    }
+
+    fn frame_count(&self) -> usize {
+        self.frame_count.unwrap_or(1)
+    }
+
+    fn frame(&self) -> usize {
+        self.frame
+    }
+
+    fn set_frame(&mut self, frame: usize) {
+        // The frame count is only known once a preview was decoded.
+        let frame = match self.frame_count {
+            Some(frame_count) => frame.min(frame_count.saturating_sub(1)),
+            None => frame,
+        };
+        if frame != self.frame {
+            self.frame = frame;
+            self.load_pending = true;
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.handle.is_none()
+    }
+
+    fn take_pending_load(&mut self) -> Option<LoadRequest> {
+        match &self.source {
+            ImageSource::File(path) if self.load_pending => {
+                self.load_pending = false;
+                self.load_generation += 1;
+                self.modified = fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok();
+                Some(LoadRequest {
+                    path: path.clone(),
+                    frame: self.frame,
+                    frame_count: self.frame_count,
+                    generation: self.load_generation,
+                    raster_size: decode::is_vector(path).then(|| self.raster_target()),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn is_current_load(&self, generation: u64) -> bool {
+        generation == self.load_generation
+    }
+
+    fn set_preview(&mut self, preview: Preview) {
+        self.native_size = Size::new(preview.width as f32, preview.height as f32);
+        self.frame_count = Some(preview.frame_count);
+        self.thumbnail_pixels = Some(preview.thumbnail);
+        self.reload_thumbnail();
+        self.rasterized_size = (preview.pixels.width(), preview.pixels.height());
+        self.preview = Some(preview.pixels);
+        self.reload_handle();
+
+        // The layer may have been scaled while an SVG was rendered.
+        if self.raster_outdated() {
+            self.load_pending = true;
+        }
+    }
+
+    fn refresh_source(&mut self) {
+        let ImageSource::File(path) = &self.source else {
+            return;
+        };
+
+        match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => {
+                if self.missing || self.modified != Some(modified) {
+                    self.frame_count = None;
+                    self.load_pending = true;
+                }
+                self.missing = false;
+            }
+            Err(_) => self.missing = !path.is_file(),
+        }
+    }
+
+    fn is_missing(&self) -> bool {
+        self.missing
+    }
+
+    fn full_resolution_load(&self) -> Option<LoadRequest> {
+        match &self.source {
+            // SVG previews are already rendered at the drawn size and export density.
+            ImageSource::File(path) if !decode::is_vector(path) => Some(LoadRequest {
+                path: path.clone(),
+                frame: self.frame,
+                frame_count: self.frame_count,
+                generation: self.load_generation,
+                raster_size: None,
+            }),
+            _ => None,
+        }
+    }
+
+    fn set_full_resolution(&mut self, pixels: Option<Arc<image::RgbaImage>>) {
+        self.full_resolution = pixels;
+        self.reload_handle();
+    }
+
+    fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    fn set_decoration(&mut self, decoration: Decoration) {
+        self.decoration = decoration;
+    }
+
+    fn on_select(&mut self) {
+        self.is_selected = true;
+    }
+
+    fn on_deselect(&mut self) {
+        self.is_selected = false;
+    }
+
+    fn to_data(&self) -> Result<ContentData, Error> {
+        Ok(ContentData::Image {
+            source: match &self.source {
+                ImageSource::File(path) => SourceData::File(path.clone()),
+                ImageSource::Memory(pixels) => project::embed_pixels(pixels)?,
+            },
+            rect: self.rect.into(),
+            fit: self.fit,
+            corner_radius: self.corner_radius,
+            rotation: self.rotation,
+            decoration: self.decoration,
+            flip_horizontal: self.flip_horizontal,
+            flip_vertical: self.flip_vertical,
+            frame: self.frame,
+        })
+    }
+
+    fn duplicate(&self, _ids: &mut IdGenerator) -> Box<dyn LayerHandler> {
+        let mut copy = self.clone();
+        copy.is_selected = false;
+        // A load still in flight only delivers to the original.
+        if matches!(copy.source, ImageSource::File(_)) && copy.preview.is_none() {
+            copy.load_pending = true;
+        }
+        Box::new(copy)
+    }
+}
+
+/// A named set of layers that is transformed as one.
+pub struct GroupLayer {
+    children: Vec<Layer>,
+    is_selected: bool,
+}
+
+impl GroupLayer {
+    pub fn new(children: Vec<Layer>) -> Self {
+        Self {
+            children,
+            is_selected: false,
+        }
+    }
+
+    /// Children that follow transforms of the group, locked ones stay where they are.
+    fn unlocked_children(&mut self) -> impl Iterator<Item = &mut Layer> {
+        self.children.iter_mut().filter(|child| !child.locked)
+    }
+}
+
+impl LayerHandler for GroupLayer {
+    fn get_preview(&self) -> Element<Message> {
+        container(text(self.children.len().to_string()).size(14))
+            .center_x(32)
+            .center_y(32)
+            .style(container::bordered_box)
+            .into()
+    }
+
+    fn draw(&self, frame: &mut Frame, opacity: f32) {
+        for child in &self.children {
+            child.draw(frame, opacity);
+        }
+
+        if self.is_selected {
+            let rect = self.get_rect();
+            frame.stroke(
+                &Path::rectangle(rect.position(), rect.size()),
+                Stroke {
+                    width: 3.0,
+                    style: Style::Solid(Color::from_rgb(1., 0., 0.)),
+                    ..Stroke::default()
+                },
+            );
+        }
+    }
+
+    fn get_rect(&self) -> Rectangle {
+        self.children
+            .iter()
+            .map(|child| child.handler.get_rect())
+            .reduce(|bounds, rect| bounds.union(&rect))
+            .unwrap_or_default()
+    }
+
+    /// Maps every unlocked child from the current bounds onto `rect`, nested groups recurse.
+    fn set_rect(&mut self, rect: Rectangle) {
+        let bounds = self.get_rect();
+        if bounds.width <= 0. || bounds.height <= 0. {
+            return;
+        }
+
+        let scale_x = rect.width / bounds.width;
+        let scale_y = rect.height / bounds.height;
+
+        for child in self.unlocked_children() {
+            let child_rect = child.handler.get_rect();
+            child.handler.set_rect(Rectangle {
+                x: rect.x + (child_rect.x - bounds.x) * scale_x,
+                y: rect.y + (child_rect.y - bounds.y) * scale_y,
+                width: child_rect.width * scale_x,
+                height: child_rect.height * scale_y,
+            });
+        }
+    }
+
+    fn get_native_size(&self) -> Size {
+        self.get_rect().size()
+    }
+
+    fn set_fit(&mut self, fit: Fit) {
+        for child in self.unlocked_children() {
+            child.handler.set_fit(fit);
+        }
+    }
+
+    fn set_corner_radius(&mut self, radius: f32) {
+        for child in self.unlocked_children() {
+            child.handler.set_corner_radius(radius);
+        }
+    }
+
+    fn set_rotation(&mut self, rotation: f32) {
+        for child in self.unlocked_children() {
+            child.handler.set_rotation(rotation);
+        }
+    }
+
+    fn set_decoration(&mut self, decoration: Decoration) {
+        for child in self.unlocked_children() {
+            child.handler.set_decoration(decoration);
+        }
     }
 
     fn on_select(&mut self) {
@@ -94,4 +792,50 @@ impl LayerHandler for ImageLayer {
     fn on_deselect(&mut self) {
         self.is_selected = false;
     }
+
+    /// Mirrors the children's positions inside the group and flips each of them.
+    fn flip(&mut self, axis: Axis) {
+        let bounds = self.get_rect();
+
+        for child in self.unlocked_children() {
+            let mut rect = child.handler.get_rect();
+            match axis {
+                Axis::Horizontal => {
+                    rect.x = bounds.x + bounds.x + bounds.width - rect.x - rect.width
+                }
+                Axis::Vertical => {
+                    rect.y = bounds.y + bounds.y + bounds.height - rect.y - rect.height
+                }
+            }
+            child.handler.set_rect(rect);
+            child.handler.flip(axis);
+        }
+    }
+
+    fn to_data(&self) -> Result<ContentData, Error> {
+        Ok(ContentData::Group {
+            children: self
+                .children
+                .iter()
+                .map(Layer::to_data)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn duplicate(&self, ids: &mut IdGenerator) -> Box<dyn LayerHandler> {
+        Box::new(GroupLayer::new(
+            self.children
+                .iter()
+                .map(|child| child.duplicate(ids))
+                .collect(),
+        ))
+    }
+
+    fn children(&self) -> Option<&Vec<Layer>> {
+        Some(&self.children)
+    }
+
+    fn children_mut(&mut self) -> Option<&mut Vec<Layer>> {
+        Some(&mut self.children)
+    }
 }
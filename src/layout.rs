@@ -0,0 +1,808 @@
+use std::fmt;
+
+use iced::{Rectangle, Size};
+
+use crate::rng::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutKind {
+    JustifiedRows,
+    Grid,
+    Masonry,
+    Mosaic,
+    Scatter,
+}
+
+impl LayoutKind {
+    pub const ALL: [LayoutKind; 5] = [
+        LayoutKind::JustifiedRows,
+        LayoutKind::Grid,
+        LayoutKind::Masonry,
+        LayoutKind::Mosaic,
+        LayoutKind::Scatter,
+    ];
+}
+
+impl fmt::Display for LayoutKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LayoutKind::JustifiedRows => "Justified rows",
+            LayoutKind::Grid => "Grid",
+            LayoutKind::Masonry => "Masonry",
+            LayoutKind::Mosaic => "Mosaic",
+            LayoutKind::Scatter => "Photo pile",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    Aspect,
+    Importance,
+}
+
+impl Weighting {
+    pub const ALL: [Weighting; 2] = [Weighting::Aspect, Weighting::Importance];
+}
+
+impl fmt::Display for Weighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Weighting::Aspect => "By aspect",
+            Weighting::Importance => "By importance",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutItem {
+    pub size: Size,
+    pub importance: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Placement {
+    pub rect: Rectangle,
+    pub rotation: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScatterOptions {
+    /// Smallest longer side of a photo, as a fraction of the canvas shorter side.
+    pub min_scale: f32,
+    pub max_scale: f32,
+    /// Maximum tilt in degrees, in either direction.
+    pub max_rotation: f32,
+    pub polaroid: bool,
+    pub shadow: bool,
+}
+
+impl Default for ScatterOptions {
+    fn default() -> Self {
+        Self {
+            min_scale: 0.3,
+            max_scale: 0.5,
+            max_rotation: 15.,
+            polaroid: true,
+            shadow: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutOptions {
+    pub kind: LayoutKind,
+    pub gutter: f32,
+    pub margin: f32,
+    pub corner_radius: f32,
+    pub crop_to_fill: bool,
+    pub weighting: Weighting,
+    pub seed: u64,
+    pub scatter: ScatterOptions,
+}
+
+impl LayoutOptions {
+    /// Whether the produced cells ignore the image aspect and need a cover crop.
+    pub fn crops(&self) -> bool {
+        match self.kind {
+            LayoutKind::Mosaic => true,
+            LayoutKind::Scatter => false,
+            _ => self.crop_to_fill,
+        }
+    }
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            kind: LayoutKind::JustifiedRows,
+            gutter: 8.,
+            margin: 16.,
+            corner_radius: 0.,
+            crop_to_fill: true,
+            weighting: Weighting::Aspect,
+            seed: 0,
+            scatter: ScatterOptions::default(),
+        }
+    }
+}
+
+/// Computes a placement for every item inside `bounds`, keeping the input order.
+pub fn arrange(options: &LayoutOptions, items: &[LayoutItem], bounds: Rectangle) -> Vec<Placement> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+
+    let inner = Rectangle {
+        x: bounds.x + options.margin,
+        y: bounds.y + options.margin,
+        width: (bounds.width - options.margin * 2.).max(1.),
+        height: (bounds.height - options.margin * 2.).max(1.),
+    };
+    let aspects: Vec<f32> = items.iter().map(|item| aspect_of(&item.size)).collect();
+
+    let rects = match options.kind {
+        LayoutKind::JustifiedRows => {
+            justified_rows(&aspects, inner, options.gutter, options.crop_to_fill)
+        }
+        LayoutKind::Grid => grid(&aspects, inner, options.gutter, options.crop_to_fill),
+        LayoutKind::Masonry => masonry(&aspects, inner, options.gutter, options.crop_to_fill),
+        LayoutKind::Mosaic => {
+            let weights: Vec<f32> = match options.weighting {
+                Weighting::Aspect => aspects.clone(),
+                Weighting::Importance => {
+                    items.iter().map(|item| item.importance.max(0.01)).collect()
+                }
+            };
+            mosaic(&weights, &aspects, inner, options.gutter, options.seed)
+        }
+        LayoutKind::Scatter => return scatter(&aspects, inner, &options.scatter, options.seed),
+    };
+
+    rects
+        .into_iter()
+        .map(|rect| Placement { rect, rotation: 0. })
+        .collect()
+}
+
+/// Returns the part of an image of `image_size` that covers `target` once centered and scaled.
+pub fn cover_rect(image_size: Size, target: Rectangle) -> Rectangle {
+    let scale = (target.width / image_size.width).max(target.height / image_size.height);
+    let width = image_size.width * scale;
+    let height = image_size.height * scale;
+
+    Rectangle {
+        x: target.x + (target.width - width) * 0.5,
+        y: target.y + (target.height - height) * 0.5,
+        width,
+        height,
+    }
+}
+
+fn aspect_of(size: &Size) -> f32 {
+    if size.width > 0. && size.height > 0. {
+        size.width / size.height
+    } else {
+        1.
+    }
+}
+
+fn justified_rows(aspects: &[f32], inner: Rectangle, gutter: f32, fill: bool) -> Vec<Rectangle> {
+    let build_rows = |target_height: f32| -> Vec<(usize, usize, f32)> {
+        let mut rows = Vec::new();
+        let mut start = 0;
+        let mut aspect_sum = 0.;
+
+        for (index, aspect) in aspects.iter().enumerate() {
+            aspect_sum += aspect;
+            let count = (index - start + 1) as f32;
+            let row_width = aspect_sum * target_height + gutter * (count - 1.);
+
+            if row_width >= inner.width {
+                let height = (inner.width - gutter * (count - 1.)) / aspect_sum;
+                rows.push((start, index + 1, height));
+                start = index + 1;
+                aspect_sum = 0.;
+            }
+        }
+
+        if start < aspects.len() {
+            let count = (aspects.len() - start) as f32;
+            let height = (inner.width - gutter * (count - 1.)) / aspect_sum;
+            rows.push((start, aspects.len(), height.min(target_height)));
+        }
+
+        rows
+    };
+    let total_height = |rows: &[(usize, usize, f32)]| {
+        rows.iter().map(|row| row.2).sum::<f32>() + gutter * (rows.len() as f32 - 1.)
+    };
+
+    let mut low = 1.;
+    let mut high = inner.height;
+    for _ in 0..32 {
+        let mid = (low + high) * 0.5;
+        if total_height(&build_rows(mid)) <= inner.height {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    // Cropping lets rows overshoot the height too, so pick whichever breaks need the least stretching.
+    let mut target_height = low;
+    if fill {
+        let distortion = |target_height: f32| {
+            (total_height(&build_rows(target_height)) / inner.height)
+                .ln()
+                .abs()
+        };
+        let mut candidate = low;
+        while candidate <= inner.height {
+            if distortion(candidate) < distortion(target_height) {
+                target_height = candidate;
+            }
+            candidate += 2.;
+        }
+    }
+
+    let mut rows = build_rows(target_height);
+    let used_height = total_height(&rows);
+    let mut y = inner.y;
+
+    if fill {
+        let gutters = gutter * (rows.len() as f32 - 1.);
+        let scale = (inner.height - gutters) / (used_height - gutters);
+        for row in &mut rows {
+            row.2 *= scale;
+        }
+    } else {
+        y += (inner.height - used_height) * 0.5;
+    }
+
+    let mut rects = Vec::with_capacity(aspects.len());
+    for (start, end, height) in rows {
+        let natural_width: f32 = aspects[start..end]
+            .iter()
+            .map(|aspect| aspect * height)
+            .sum();
+        let gutters = gutter * (end - start - 1) as f32;
+        let stretch = if fill {
+            (inner.width - gutters) / natural_width
+        } else {
+            1.
+        };
+        let mut x = inner.x + (inner.width - natural_width * stretch - gutters) * 0.5;
+
+        for aspect in &aspects[start..end] {
+            let width = aspect * height * stretch;
+            rects.push(Rectangle {
+                x,
+                y,
+                width,
+                height,
+            });
+            x += width + gutter;
+        }
+
+        y += height + gutter;
+    }
+
+    rects
+}
+
+fn grid(aspects: &[f32], inner: Rectangle, gutter: f32, fill: bool) -> Vec<Rectangle> {
+    let count = aspects.len();
+    let average_aspect = aspects.iter().sum::<f32>() / count as f32;
+
+    let cell_size = |columns: usize| {
+        let rows = count.div_ceil(columns);
+        Size {
+            width: (inner.width - gutter * (columns - 1) as f32) / columns as f32,
+            height: (inner.height - gutter * (rows - 1) as f32) / rows as f32,
+        }
+    };
+    let covered_area = |columns: usize| {
+        let cell = cell_size(columns);
+        aspects
+            .iter()
+            .map(|aspect| {
+                let fitted = contain_size(*aspect, cell);
+                fitted.width * fitted.height
+            })
+            .sum::<f32>()
+    };
+
+    let columns = (1..=count)
+        .filter(|columns| {
+            let cell = cell_size(*columns);
+            cell.width > 0. && cell.height > 0.
+        })
+        .max_by(|a, b| {
+            if fill {
+                let distance = |columns: usize| {
+                    let cell = cell_size(columns);
+                    -((cell.width / cell.height) / average_aspect).ln().abs()
+                };
+                distance(*a).total_cmp(&distance(*b))
+            } else {
+                covered_area(*a).total_cmp(&covered_area(*b))
+            }
+        })
+        .unwrap_or(1);
+    let cell = cell_size(columns);
+
+    aspects
+        .iter()
+        .enumerate()
+        .map(|(index, aspect)| {
+            let column = index % columns;
+            let row = index / columns;
+            let cell_rect = Rectangle {
+                x: inner.x + (cell.width + gutter) * column as f32,
+                y: inner.y + (cell.height + gutter) * row as f32,
+                width: cell.width,
+                height: cell.height,
+            };
+
+            if fill {
+                cell_rect
+            } else {
+                let fitted = contain_size(*aspect, cell);
+                Rectangle {
+                    x: cell_rect.x + (cell.width - fitted.width) * 0.5,
+                    y: cell_rect.y + (cell.height - fitted.height) * 0.5,
+                    width: fitted.width,
+                    height: fitted.height,
+                }
+            }
+        })
+        .collect()
+}
+
+fn masonry(aspects: &[f32], inner: Rectangle, gutter: f32, fill: bool) -> Vec<Rectangle> {
+    let place = |columns: usize, width: f32| {
+        let mut heights = vec![0f32; columns];
+        let mut counts = vec![0usize; columns];
+        let mut placements = Vec::with_capacity(aspects.len());
+
+        for aspect in aspects {
+            let (column, _) = heights
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap();
+            let height = width / aspect;
+            placements.push((column, counts[column], heights[column], height));
+            heights[column] += height + gutter;
+            counts[column] += 1;
+        }
+
+        (placements, counts)
+    };
+    // Shrinks the column width until the tallest column fits into the canvas height.
+    let fitted_width = |columns: usize| {
+        let width = (inner.width - gutter * (columns - 1) as f32) / columns as f32;
+        let (placements, counts) = place(columns, width);
+
+        (0..columns)
+            .filter(|column| counts[*column] > 0)
+            .map(|column| {
+                let inverse_aspects: f32 = placements
+                    .iter()
+                    .filter(|placement| placement.0 == column)
+                    .map(|placement| placement.3 / width)
+                    .sum();
+                (inner.height - gutter * (counts[column] - 1) as f32) / inverse_aspects
+            })
+            .fold(width, f32::min)
+    };
+
+    let columns = (1..=aspects.len())
+        .filter(|columns| inner.width - gutter * (*columns - 1) as f32 > 0.)
+        .max_by(|a, b| {
+            let covered = |columns: usize| {
+                let width = if fill {
+                    (inner.width - gutter * (columns - 1) as f32) / columns as f32
+                } else {
+                    fitted_width(columns)
+                };
+                let (placements, _) = place(columns, width);
+                if fill {
+                    // Columns are stretched to the full height, so favour the least distortion.
+                    let distortion: f32 = (0..columns)
+                        .map(|column| {
+                            let column_height = placements
+                                .iter()
+                                .filter(|placement| placement.0 == column)
+                                .map(|placement| placement.3 + gutter)
+                                .sum::<f32>()
+                                - gutter;
+                            (column_height / inner.height).ln().abs()
+                        })
+                        .sum();
+                    -distortion
+                } else {
+                    placements.iter().map(|placement| width * placement.3).sum()
+                }
+            };
+            covered(*a).total_cmp(&covered(*b))
+        })
+        .unwrap_or(1);
+
+    if fill {
+        let width = (inner.width - gutter * (columns - 1) as f32) / columns as f32;
+        let (placements, counts) = place(columns, width);
+        let content: Vec<f32> = (0..columns)
+            .map(|column| {
+                placements
+                    .iter()
+                    .filter(|placement| placement.0 == column)
+                    .map(|placement| placement.3)
+                    .sum()
+            })
+            .collect();
+
+        placements
+            .iter()
+            .map(|(column, index, top, height)| {
+                let gutters = gutter * (counts[*column] - 1) as f32;
+                let stretch = (inner.height - gutters) / content[*column];
+                let gutters_above = gutter * *index as f32;
+
+                Rectangle {
+                    x: inner.x + (width + gutter) * *column as f32,
+                    y: inner.y + (top - gutters_above) * stretch + gutters_above,
+                    width,
+                    height: height * stretch,
+                }
+            })
+            .collect()
+    } else {
+        let width = fitted_width(columns);
+        let (placements, _) = place(columns, width);
+        let total_width = width * columns as f32 + gutter * (columns - 1) as f32;
+        let tallest = placements
+            .iter()
+            .map(|placement| placement.2 + placement.3)
+            .fold(0f32, f32::max);
+        let offset_x = inner.x + (inner.width - total_width) * 0.5;
+        let offset_y = inner.y + (inner.height - tallest) * 0.5;
+
+        placements
+            .iter()
+            .map(|(column, _, top, height)| Rectangle {
+                x: offset_x + (width + gutter) * *column as f32,
+                y: offset_y + top,
+                width,
+                height: *height,
+            })
+            .collect()
+    }
+}
+
+fn mosaic(
+    weights: &[f32],
+    aspects: &[f32],
+    inner: Rectangle,
+    gutter: f32,
+    seed: u64,
+) -> Vec<Rectangle> {
+    const ATTEMPTS: usize = 64;
+
+    let mut rng = Rng::new(seed);
+    let mut best = Vec::new();
+    let mut best_cost = f32::INFINITY;
+
+    // Every attempt shuffles the order, the least cropped result wins.
+    for _ in 0..ATTEMPTS {
+        let mut order: Vec<usize> = (0..weights.len()).collect();
+        rng.shuffle(&mut order);
+
+        let mut rects = vec![Rectangle::default(); weights.len()];
+        split_cells(&order, weights, inner, gutter, &mut rng, &mut rects);
+
+        let cost: f32 = rects
+            .iter()
+            .zip(aspects)
+            .map(|(rect, aspect)| ((rect.width / rect.height.max(1.)) / aspect).ln().abs())
+            .sum();
+        if cost < best_cost {
+            best_cost = cost;
+            best = rects;
+        }
+    }
+
+    best
+}
+
+/// Recursively halves `cell` along its longer side, sizing both parts by their summed weights.
+fn split_cells(
+    order: &[usize],
+    weights: &[f32],
+    cell: Rectangle,
+    gutter: f32,
+    rng: &mut Rng,
+    rects: &mut [Rectangle],
+) {
+    if let [index] = order {
+        rects[*index] = cell;
+        return;
+    }
+
+    let total: f32 = order.iter().map(|index| weights[*index]).sum();
+    let mut prefix = 0.;
+    let split = (1..order.len())
+        .map(|split| {
+            prefix += weights[order[split - 1]];
+            // Jitter keeps shuffles varied while still preferring balanced halves.
+            let balance = (prefix / total - 0.5).abs() + rng.range(0., 0.15);
+            (split, balance)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(split, _)| split)
+        .unwrap_or(1);
+
+    let (first, second) = order.split_at(split);
+    let ratio = first.iter().map(|index| weights[*index]).sum::<f32>() / total;
+
+    let (first_cell, second_cell) = if cell.width >= cell.height {
+        let width = (cell.width - gutter).max(0.);
+        let first_width = width * ratio;
+        (
+            Rectangle {
+                width: first_width,
+                ..cell
+            },
+            Rectangle {
+                x: cell.x + first_width + gutter,
+                width: width - first_width,
+                ..cell
+            },
+        )
+    } else {
+        let height = (cell.height - gutter).max(0.);
+        let first_height = height * ratio;
+        (
+            Rectangle {
+                height: first_height,
+                ..cell
+            },
+            Rectangle {
+                y: cell.y + first_height + gutter,
+                height: height - first_height,
+                ..cell
+            },
+        )
+    };
+
+    split_cells(first, weights, first_cell, gutter, rng, rects);
+    split_cells(second, weights, second_cell, gutter, rng, rects);
+}
+
+fn scatter(
+    aspects: &[f32],
+    inner: Rectangle,
+    options: &ScatterOptions,
+    seed: u64,
+) -> Vec<Placement> {
+    const RELAXATION_STEPS: usize = 64;
+    // Photos may cover this fraction of each other before being pushed apart.
+    const ALLOWED_OVERLAP: f32 = 0.25;
+
+    let mut rng = Rng::new(seed);
+    let short_side = inner.width.min(inner.height);
+    let min_scale = options.min_scale.min(options.max_scale);
+    let max_scale = options.min_scale.max(options.max_scale);
+
+    let mut placements: Vec<Placement> = aspects
+        .iter()
+        .map(|aspect| {
+            let longer_side = short_side * rng.range(min_scale, max_scale);
+            let (width, height) = if *aspect >= 1. {
+                (longer_side, longer_side / aspect)
+            } else {
+                (longer_side * aspect, longer_side)
+            };
+
+            Placement {
+                rect: Rectangle {
+                    x: rng.range(inner.x, (inner.x + inner.width - width).max(inner.x)),
+                    y: rng.range(inner.y, (inner.y + inner.height - height).max(inner.y)),
+                    width,
+                    height,
+                },
+                rotation: rng
+                    .range(-options.max_rotation, options.max_rotation)
+                    .to_radians(),
+            }
+        })
+        .collect();
+
+    for _ in 0..RELAXATION_STEPS {
+        let mut moved = false;
+
+        for first in 0..placements.len() {
+            for second in first + 1..placements.len() {
+                let a = placements[first].rect;
+                let b = placements[second].rect;
+
+                let overlap_x = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+                let overlap_y = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+                let excess_x = overlap_x - a.width.min(b.width) * ALLOWED_OVERLAP;
+                let excess_y = overlap_y - a.height.min(b.height) * ALLOWED_OVERLAP;
+
+                if excess_x <= 0. || excess_y <= 0. {
+                    continue;
+                }
+                moved = true;
+
+                let (a_center, b_center) = (a.center(), b.center());
+                if excess_x < excess_y {
+                    let push = if a_center.x < b_center.x { -0.5 } else { 0.5 } * excess_x;
+                    placements[first].rect.x += push;
+                    placements[second].rect.x -= push;
+                } else {
+                    let push = if a_center.y < b_center.y { -0.5 } else { 0.5 } * excess_y;
+                    placements[first].rect.y += push;
+                    placements[second].rect.y -= push;
+                }
+            }
+        }
+
+        for placement in &mut placements {
+            let rect = &mut placement.rect;
+            rect.x = rect
+                .x
+                .clamp(inner.x, (inner.x + inner.width - rect.width).max(inner.x));
+            rect.y = rect
+                .y
+                .clamp(inner.y, (inner.y + inner.height - rect.height).max(inner.y));
+        }
+
+        if !moved {
+            break;
+        }
+    }
+
+    placements
+}
+
+fn contain_size(aspect: f32, cell: Size) -> Size {
+    if aspect > cell.width / cell.height {
+        Size {
+            width: cell.width,
+            height: cell.width / aspect,
+        }
+    } else {
+        Size {
+            width: cell.height * aspect,
+            height: cell.height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Rectangle = Rectangle {
+        x: 0.,
+        y: 0.,
+        width: 1280.,
+        height: 720.,
+    };
+
+    /// Slack for rounding when comparing edges.
+    const EPSILON: f32 = 0.01;
+
+    fn items() -> Vec<LayoutItem> {
+        [
+            (1920., 1080.),
+            (1080., 1920.),
+            (800., 800.),
+            (3000., 1000.),
+            (640., 480.),
+            (480., 640.),
+            (1200., 900.),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, (width, height))| LayoutItem {
+            size: Size::new(width, height),
+            importance: 1. + index as f32,
+        })
+        .collect()
+    }
+
+    fn placements(kind: LayoutKind, crop_to_fill: bool) -> Vec<Placement> {
+        let options = LayoutOptions {
+            kind,
+            crop_to_fill,
+            ..LayoutOptions::default()
+        };
+        arrange(&options, &items(), BOUNDS)
+    }
+
+    #[test]
+    fn every_layout_stays_inside_the_margins() {
+        let margin = LayoutOptions::default().margin;
+
+        for kind in LayoutKind::ALL {
+            for crop_to_fill in [true, false] {
+                let placements = placements(kind, crop_to_fill);
+                assert_eq!(placements.len(), items().len(), "{kind}");
+
+                for Placement { rect, .. } in placements {
+                    assert!(rect.width > 0. && rect.height > 0., "{kind}: {rect:?}");
+                    assert!(
+                        rect.x >= BOUNDS.x + margin - EPSILON
+                            && rect.y >= BOUNDS.y + margin - EPSILON
+                            && rect.x + rect.width <= BOUNDS.x + BOUNDS.width - margin + EPSILON
+                            && rect.y + rect.height <= BOUNDS.y + BOUNDS.height - margin + EPSILON,
+                        "{kind}, crop {crop_to_fill}: {rect:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiled_layouts_never_overlap() {
+        // Photo piles overlap on purpose.
+        for kind in LayoutKind::ALL
+            .into_iter()
+            .filter(|kind| *kind != LayoutKind::Scatter)
+        {
+            for crop_to_fill in [true, false] {
+                let rects: Vec<Rectangle> = placements(kind, crop_to_fill)
+                    .into_iter()
+                    .map(|placement| placement.rect)
+                    .collect();
+
+                for (index, a) in rects.iter().enumerate() {
+                    for b in &rects[index + 1..] {
+                        let overlap_x = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+                        let overlap_y = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+                        assert!(
+                            overlap_x <= EPSILON || overlap_y <= EPSILON,
+                            "{kind}, crop {crop_to_fill}: {a:?} overlaps {b:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_layout() {
+        for kind in [LayoutKind::Mosaic, LayoutKind::Scatter] {
+            let first = placements(kind, true);
+            let second = placements(kind, true);
+            assert!(
+                first
+                    .iter()
+                    .zip(&second)
+                    .all(|(a, b)| a.rect == b.rect && a.rotation == b.rotation),
+                "{kind}"
+            );
+        }
+    }
+
+    #[test]
+    fn cover_rect_fills_the_target() {
+        let target = Rectangle::new(iced::Point::new(10., 20.), Size::new(100., 100.));
+        let cover = cover_rect(Size::new(200., 100.), target);
+
+        assert_eq!(cover.height, 100.);
+        assert_eq!(cover.width, 200.);
+        assert_eq!(cover.center(), target.center());
+    }
+
+    #[test]
+    fn no_items_give_no_placements() {
+        assert!(arrange(&LayoutOptions::default(), &[], BOUNDS).is_empty());
+    }
+}
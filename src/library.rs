@@ -0,0 +1,149 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, import, utils};
+
+const LIBRARY_FILE: &str = "library.json";
+
+/// Folders of wallpaper material shown in the asset browser, with the favorites and tags
+/// given to their images. Saved as `library.json` in the config directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Library {
+    pub folders: Vec<PathBuf>,
+    pub favorites: BTreeSet<PathBuf>,
+    pub tags: BTreeMap<PathBuf, BTreeSet<String>>,
+}
+
+impl Library {
+    /// Reads the library file, starting empty when it is missing or invalid.
+    ///
+    /// Why an existing file was ignored is returned alongside.
+    pub fn load() -> (Self, Option<Error>) {
+        let Some(contents) = library_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return (Self::default(), None);
+        };
+
+        match serde_json::from_str(&contents) {
+            Ok(library) => (library, None),
+            Err(error) => (
+                Self::default(),
+                Some(Error::Settings {
+                    file: LIBRARY_FILE,
+                    reason: error.to_string(),
+                }),
+            ),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = library_path() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    pub fn add_folder(&mut self, folder: PathBuf) {
+        if !self.folders.contains(&folder) {
+            self.folders.push(folder);
+        }
+    }
+
+    pub fn is_favorite(&self, path: &Path) -> bool {
+        self.favorites.contains(path)
+    }
+
+    pub fn toggle_favorite(&mut self, path: &Path) {
+        if !self.favorites.remove(path) {
+            self.favorites.insert(path.to_path_buf());
+        }
+    }
+
+    pub fn tags_of(&self, path: &Path) -> impl Iterator<Item = &str> {
+        self.tags
+            .get(path)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Tags are stored trimmed and lowercase so "Sky" and "sky " are the same tag.
+    pub fn add_tag(&mut self, path: &Path, tag: &str) {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() {
+            self.tags.entry(path.to_path_buf()).or_default().insert(tag);
+        }
+    }
+
+    pub fn remove_tag(&mut self, path: &Path, tag: &str) {
+        if let Some(tags) = self.tags.get_mut(path) {
+            tags.remove(tag);
+            if tags.is_empty() {
+                self.tags.remove(path);
+            }
+        }
+    }
+
+    /// Every tag in use, sorted.
+    pub fn all_tags(&self) -> Vec<String> {
+        let tags: BTreeSet<&String> = self.tags.values().flatten().collect();
+        tags.into_iter().cloned().collect()
+    }
+
+    /// Whether `path` passes the browser's filters. The query matches file names and tags.
+    pub fn matches(&self, path: &Path, filter: &Filter) -> bool {
+        if filter.favorites_only && !self.is_favorite(path) {
+            return false;
+        }
+        if let Some(tag) = &filter.tag {
+            if !self.tags_of(path).any(|other| other == tag) {
+                return false;
+            }
+        }
+
+        let query = filter.query.trim().to_lowercase();
+        query.is_empty()
+            || path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().to_lowercase().contains(&query))
+            || self.tags_of(path).any(|tag| tag.contains(&query))
+    }
+}
+
+/// What the asset browser shows of the library.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub query: String,
+    pub favorites_only: bool,
+    pub tag: Option<String>,
+}
+
+/// Images in the library folders and their subfolders, sorted by file name.
+pub fn scan(folders: &[PathBuf]) -> Vec<PathBuf> {
+    let mut images = Vec::new();
+    for folder in folders {
+        import::collect_files(folder, true, &mut images);
+    }
+
+    images.sort_by_cached_key(|path| {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase());
+        (name, path.clone())
+    });
+    images.dedup();
+    images
+}
+
+fn library_path() -> Option<PathBuf> {
+    utils::config_dir().map(|dir| dir.join(LIBRARY_FILE))
+}
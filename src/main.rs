@@ -1,13 +1,25 @@
 use bg_maker::BgMaker;
 
+mod arrange;
 mod bg_maker;
+mod decode;
+mod error;
 mod id;
+mod import;
+mod keymap;
 mod layer;
 mod layer_handler;
+mod layout;
+mod library;
 mod maker_canvas;
+mod palette;
+mod project;
+mod rng;
 mod simulator;
 mod styles;
+mod thumbnails;
 mod utils;
+mod watcher;
 
 fn main() -> iced::Result {
     iced::application(BgMaker::new, BgMaker::update, BgMaker::view)
@@ -1,32 +1,66 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path as FilePath, PathBuf},
+    sync::Arc,
+};
 
 use iced::{
     Color, Element, Point, Rectangle, Renderer, Size, Theme,
     advanced::graphics::geometry::Style,
     mouse,
-    widget::canvas::{self, Frame, Path, Stroke},
+    widget::canvas::{self, Frame, LineDash, Path, Stroke},
+    window,
 };
 
-use layer_handler::ImageLayer;
+use layer_handler::{Decoration, Fit, GroupLayer, ImageLayer, LayerHandler};
 
 use crate::{
-    bg_maker::{Message, PngError},
+    arrange::{self, Align, AlignTarget, Axis},
+    bg_maker::Message,
+    decode::{self, LoadRequest, Preview},
+    error::Error,
     id::{Id, IdGenerator},
-    layer::Layer,
+    layer::{self, Edges, Layer},
     layer_handler,
+    layout::{self, LayoutItem, LayoutKind, LayoutOptions},
+    project::{self, ContentData, LayerData, ProjectData, SourceData},
     simulator::Simulator,
 };
 
+/// Offset between images dropped together so they don't cover each other exactly.
+pub const DROP_CASCADE: f32 = 24.;
+
+/// Pixel density of exported images relative to the canvas size.
+pub const EXPORT_SCALE_FACTOR: f32 = 2.0;
+
+/// Full resolution pixels of the image layers for an export, with the load generation
+/// they were decoded for.
+#[derive(Clone)]
+pub struct ExportPixels(Vec<(Id, u64, Arc<image::RgbaImage>)>);
+
+impl std::fmt::Debug for ExportPixels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ExportPixels({} images)", self.0.len())
+    }
+}
+
+/// Size of the placeholder shown for a new image until its size is known.
+const PLACEHOLDER_SIZE: Size = Size::new(240., 160.);
+
 pub struct MakerCanvas {
     pub layers: Vec<Layer>,
     id_generator: IdGenerator,
-    selected_layer: Option<usize>,
     width: f32,
     height: f32,
     zoom: f32,
     shift_held: bool,
+    ctrl_held: bool,
     ignored_delta_bank: Point,
     snap_point: Point,
+    groups_created: usize,
+    /// New images that are placed once their size is decoded.
+    awaiting_placement: Vec<Id>,
+    /// Image dragged from the asset browser, added where it is released over the canvas.
+    pub dragged_asset: Option<PathBuf>,
 }
 
 impl MakerCanvas {
@@ -34,16 +68,24 @@ impl MakerCanvas {
         Self {
             layers: Vec::new(),
             id_generator: IdGenerator::new(),
-            selected_layer: None,
             width,
             height,
             zoom: 1.,
             shift_held: false,
+            ctrl_held: false,
             ignored_delta_bank: Point::ORIGIN,
             snap_point: Point::ORIGIN,
+            groups_created: 0,
+            awaiting_placement: Vec::new(),
+            dragged_asset: None,
         }
     }
 
+    pub fn set_size(&mut self, size: Size) {
+        self.width = size.width;
+        self.height = size.height;
+    }
+
     pub fn view(&self) -> Element<Message> {
         let canvas = canvas::Canvas::new(self)
             .width(self.width * self.zoom)
@@ -53,22 +95,151 @@ impl MakerCanvas {
     }
 
     pub fn add_image_layer(&mut self, image_path: PathBuf) {
-        let name = image_path
-            .file_name()
-            .and_then(|os_str| os_str.to_str())
-            .unwrap_or_else(|| "default_name")
-            .to_string();
-        let handler = Box::new(ImageLayer::new(image_path));
-        self.layers
-            .push(Layer::new(self.id_generator.generate(), name, handler));
+        self.add_image_layer_at(image_path, self.center());
     }
 
-    pub fn remove_layer(&mut self, id: Id) {
-        if let Some(index) = self.layers.iter().position(|layer| layer.id == id) {
-            self.layers.remove(index);
+    /// Adds an image centered on `center`, scaled down to fit the canvas when it is larger.
+    ///
+    /// The image shows as a placeholder until [`MakerCanvas::finish_loading`] gets its
+    /// preview.
+    pub fn add_image_layer_at(&mut self, image_path: PathBuf, center: Point) {
+        let name = layer_name(&image_path);
+        let rect = Rectangle::new(
+            Point::new(
+                center.x - PLACEHOLDER_SIZE.width * 0.5,
+                center.y - PLACEHOLDER_SIZE.height * 0.5,
+            ),
+            PLACEHOLDER_SIZE,
+        );
+        let handler = Box::new(ImageLayer::new(image_path, rect));
+        let id = self.id_generator.generate();
+        self.layers.push(Layer::new(id, name, handler));
+        self.awaiting_placement.push(id);
+    }
+
+    /// Files of placeholder layers that still have to be decoded, each returned once.
+    pub fn take_pending_loads(&mut self) -> Vec<(Id, LoadRequest)> {
+        let mut loads = Vec::new();
+        collect_pending_loads(&mut self.layers, &mut loads);
+        loads
+    }
+
+    /// Shows the preview decoded for a layer, new images get their size and are placed
+    /// where their placeholder was. Results of superseded loads are dropped.
+    pub fn finish_loading(&mut self, id: Id, generation: u64, preview: Preview) {
+        let Some(layer) = self
+            .find_layer_mut(id)
+            .filter(|layer| layer.handler.is_current_load(generation))
+        else {
+            return;
+        };
+        layer.handler.set_preview(preview);
+        let center = layer.handler.get_rect().center();
+
+        if let Some(index) = self
+            .awaiting_placement
+            .iter()
+            .position(|other| *other == id)
+        {
+            self.awaiting_placement.remove(index);
+            self.place_layer(id, center);
         }
     }
 
+    /// Removes a layer whose file couldn't be decoded, unless it already shows older pixels
+    /// or its file went missing and can be relinked. Returns `false` for failures of
+    /// superseded loads, which are ignored.
+    pub fn fail_loading(&mut self, id: Id, generation: u64) -> bool {
+        let Some(layer) = self
+            .find_layer_mut(id)
+            .filter(|layer| layer.handler.is_current_load(generation))
+        else {
+            return false;
+        };
+        layer.handler.refresh_source();
+        if layer.handler.is_loading() && !layer.handler.is_missing() {
+            self.remove_layer(id);
+        }
+        self.awaiting_placement.retain(|other| *other != id);
+        true
+    }
+
+    /// Rechecks the layers showing `path`, or a file inside it when a whole folder was
+    /// deleted or renamed, after it changed on disk.
+    pub fn refresh_source(&mut self, path: &FilePath) {
+        refresh_nested(&mut self.layers, path);
+    }
+
+    /// Folders holding the files of every image layer, sorted and without duplicates.
+    pub fn source_folders(&self) -> Vec<PathBuf> {
+        let mut folders = Vec::new();
+        collect_source_folders(&self.layers, &mut folders);
+        folders.sort();
+        folders.dedup();
+        folders
+    }
+
+    /// Id, name and path of the layers whose file can't be found.
+    pub fn missing_layers(&self) -> Vec<(Id, String, PathBuf)> {
+        let mut missing = Vec::new();
+        collect_missing(&self.layers, &mut missing);
+        missing
+    }
+
+    /// Points missing layers at the files among `candidates` with the same name, returns
+    /// how many were relinked.
+    pub fn relink_missing(&mut self, candidates: &[PathBuf]) -> usize {
+        let mut relinked = 0;
+        for (id, _, path) in self.missing_layers() {
+            let Some(candidate) = candidates
+                .iter()
+                .find(|candidate| candidate.file_name() == path.file_name())
+            else {
+                continue;
+            };
+            if self.replace_layer_source(id, candidate.clone()).is_ok() {
+                relinked += 1;
+            }
+        }
+        relinked
+    }
+
+    /// Adds decoded pixels as a new layer in the middle of the canvas and selects it.
+    pub fn add_pixels_layer(&mut self, name: String, pixels: image::RgbaImage) {
+        let handler = Box::new(ImageLayer::from_pixels(pixels));
+        let mut layer = Layer::new(self.id_generator.generate(), name, handler);
+        self.deselect_layers();
+        layer.on_select();
+        let id = layer.id;
+        self.layers.push(layer);
+        self.place_layer(id, self.center());
+    }
+
+    fn place_layer(&mut self, id: Id, center: Point) {
+        let (width, height) = (self.width, self.height);
+        let Some(layer) = self.find_layer_mut(id) else {
+            return;
+        };
+
+        let native = layer.handler.get_native_size();
+        let scale = (width / native.width).min(height / native.height).min(1.);
+        let size = Size::new(native.width * scale, native.height * scale);
+        let x = (center.x - size.width * 0.5).clamp(0., (width - size.width).max(0.));
+        let y = (center.y - size.height * 0.5).clamp(0., (height - size.height).max(0.));
+
+        layer
+            .handler
+            .set_rect(Rectangle::new(Point::new(x, y), size));
+    }
+
+    pub fn center(&self) -> Point {
+        self.bounds().center()
+    }
+
+    pub fn remove_layer(&mut self, id: Id) {
+        remove_nested(&mut self.layers, id);
+    }
+
     pub fn layer_up(&mut self, id: Id) {
         if let Some(index) = self.layers.iter().position(|layer| layer.id == id) {
             if index > 0 {
@@ -85,13 +256,543 @@ impl MakerCanvas {
         }
     }
 
+    pub fn copy_selection(&self) -> Result<Vec<LayerData>, Error> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.is_selected)
+            .map(Layer::to_data)
+            .collect()
+    }
+
+    pub fn delete_selection(&mut self) {
+        self.layers.retain(|layer| !layer.is_selected);
+    }
+
+    /// Adds copies of `layers` on top of the stack, moved by `offset`, and selects them.
+    ///
+    /// Layers whose image can't be loaded are left out and their errors returned.
+    pub fn paste_layers(&mut self, layers: &[LayerData], offset: Point) -> Vec<Error> {
+        let mut errors = Vec::new();
+        self.deselect_layers();
+
+        for data in layers {
+            let Some(mut layer) = self.restore_layer(data, &mut errors) else {
+                continue;
+            };
+            layer.move_by(offset);
+            layer.on_select();
+            self.layers.push(layer);
+        }
+
+        errors
+    }
+
+    /// Adds copies of the selected layers on top of the stack and selects them.
+    pub fn duplicate_selection(&mut self) {
+        const DUPLICATE_OFFSET: f32 = 10.;
+
+        let copies: Vec<Layer> = self
+            .layers
+            .iter()
+            .filter(|layer| layer.is_selected)
+            .map(|layer| layer.duplicate(&mut self.id_generator))
+            .collect();
+
+        self.deselect_layers();
+        for mut layer in copies {
+            layer.move_by(Point::new(DUPLICATE_OFFSET, DUPLICATE_OFFSET));
+            layer.on_select();
+            self.layers.push(layer);
+        }
+    }
+
+    fn restore_layer(&mut self, data: &LayerData, errors: &mut Vec<Error>) -> Option<Layer> {
+        let handler: Box<dyn LayerHandler> = match &data.content {
+            ContentData::Image {
+                source,
+                rect,
+                fit,
+                corner_radius,
+                rotation,
+                decoration,
+                flip_horizontal,
+                flip_vertical,
+                frame,
+            } => {
+                let image = match source {
+                    SourceData::File(path) => Ok(ImageLayer::new(path.clone(), (*rect).into())),
+                    SourceData::Embedded(embedded) => project::decode_embedded(embedded)
+                        .map(ImageLayer::from_pixels)
+                        .map_err(|reason| Error::EmbeddedImage {
+                            name: data.name.clone(),
+                            reason,
+                        }),
+                };
+                let mut image = match image {
+                    Ok(image) => image,
+                    Err(error) => {
+                        errors.push(error);
+                        return None;
+                    }
+                };
+                image.set_frame(*frame);
+                if *flip_horizontal {
+                    image.flip(Axis::Horizontal);
+                }
+                if *flip_vertical {
+                    image.flip(Axis::Vertical);
+                }
+                image.set_rect((*rect).into());
+                image.set_fit(*fit);
+                image.set_corner_radius(*corner_radius);
+                image.set_rotation(*rotation);
+                image.set_decoration(*decoration);
+                Box::new(image)
+            }
+            ContentData::Group { children } => {
+                let children = children
+                    .iter()
+                    .filter_map(|child| self.restore_layer(child, errors))
+                    .collect();
+                Box::new(GroupLayer::new(children))
+            }
+        };
+
+        let mut layer = Layer::new(self.id_generator.generate(), data.name.clone(), handler);
+        layer.importance = data.importance;
+        layer.hidden = data.hidden;
+        layer.locked = data.locked;
+        layer.opacity = data.opacity;
+        layer.expanded = data.expanded;
+        Some(layer)
+    }
+
+    pub fn to_project(&self, layout_seed: u64) -> Result<ProjectData, Error> {
+        Ok(ProjectData {
+            width: self.width,
+            height: self.height,
+            layers: self
+                .layers
+                .iter()
+                .map(Layer::to_data)
+                .collect::<Result<_, _>>()?,
+            layout_seed,
+        })
+    }
+
+    /// Replaces the whole canvas with a saved project.
+    pub fn load_project(&mut self, project: &ProjectData) -> Vec<Error> {
+        self.set_size(project.size());
+        self.layers.clear();
+        let errors = self.paste_layers(&project.layers, Point::ORIGIN);
+        self.deselect_layers();
+        errors
+    }
+
+    pub fn flip_selection(&mut self, axis: Axis) {
+        for index in self.selected_indices() {
+            self.layers[index].handler.flip(axis);
+        }
+    }
+
+    /// Gives the selected layers their source image's pixel size, keeping their position.
+    pub fn reset_selection_size(&mut self) {
+        for index in self.movable_indices() {
+            let handler = &mut self.layers[index].handler;
+            let rect = handler.get_rect();
+            handler.set_rect(Rectangle::new(rect.position(), handler.get_native_size()));
+        }
+    }
+
+    /// Swaps the file of a layer, which is renamed after the new file unless the user gave
+    /// it another name than its old file's.
+    pub fn replace_layer_source(&mut self, id: Id, path: PathBuf) -> Result<(), Error> {
+        let Some(layer) = self.find_layer_mut(id) else {
+            return Ok(());
+        };
+
+        let named_after_file = layer
+            .handler
+            .source_path()
+            .is_some_and(|old_path| layer.name == layer_name(old_path));
+        let name = layer_name(&path);
+        layer.handler.set_source(path)?;
+        if named_after_file {
+            layer.name = name;
+        }
+        Ok(())
+    }
+
+    /// Index of a top-level layer.
+    pub fn layer_index(&self, id: Id) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.id == id)
+    }
+
+    /// Topmost unlocked image under `position`, looking inside groups.
+    pub fn image_layer_at(&self, position: Point) -> Option<&Layer> {
+        fn find_in(layers: &[Layer], position: Point) -> Option<&Layer> {
+            layers
+                .iter()
+                .rev()
+                .filter(|layer| layer.is_selectable())
+                .find_map(|layer| match layer.handler.children() {
+                    Some(children) => find_in(children, position),
+                    None if layer.handler.source_path().is_some() && layer.contains(position) => {
+                        Some(layer)
+                    }
+                    None => None,
+                })
+        }
+
+        find_in(&self.layers, position)
+    }
+
+    pub fn find_layer(&self, id: Id) -> Option<&Layer> {
+        self.layers.iter().find_map(|layer| layer.find(id))
+    }
+
+    pub fn nudge_selection(&mut self, delta: Point) {
+        for index in self.movable_indices() {
+            self.layers[index].move_by(delta);
+        }
+    }
+
+    /// Selects the next selectable layer after the current selection, wrapping around.
+    pub fn cycle_selection(&mut self, backwards: bool) {
+        let count = self.layers.len();
+        if count == 0 {
+            return;
+        }
+
+        let current = if backwards {
+            self.selected_indices().first().copied()
+        } else {
+            self.selected_indices().last().copied()
+        };
+
+        let next = (1..=count)
+            .map(|step| match (current, backwards) {
+                (Some(index), false) => (index + step) % count,
+                (Some(index), true) => (index + count - step % count) % count,
+                (None, false) => step - 1,
+                (None, true) => count - step,
+            })
+            .find(|index| self.layers[*index].is_selectable());
+
+        if let Some(index) = next {
+            self.select_layer(index);
+        }
+    }
+
+    /// Moves a layer so it ends up at `to` in the stack.
+    pub fn move_layer_to(&mut self, from: usize, to: usize) {
+        if from >= self.layers.len() || to >= self.layers.len() {
+            return;
+        }
+
+        let layer = self.layers.remove(from);
+        self.layers.insert(to, layer);
+    }
+
+    /// Moves the selected layers above every other layer, keeping their stacking order.
+    pub fn bring_selection_to_front(&mut self) {
+        let (mut selected, rest): (Vec<Layer>, Vec<Layer>) = std::mem::take(&mut self.layers)
+            .into_iter()
+            .partition(|layer| layer.is_selected);
+        self.layers = rest;
+        self.layers.append(&mut selected);
+    }
+
+    /// Moves the selected layers below every other layer, keeping their stacking order.
+    pub fn send_selection_to_back(&mut self) {
+        let (mut selected, mut rest): (Vec<Layer>, Vec<Layer>) = std::mem::take(&mut self.layers)
+            .into_iter()
+            .partition(|layer| layer.is_selected);
+        selected.append(&mut rest);
+        self.layers = selected;
+    }
+
+    /// Finds a layer anywhere in the tree, including inside groups.
+    pub fn find_layer_mut(&mut self, id: Id) -> Option<&mut Layer> {
+        self.layers.iter_mut().find_map(|layer| layer.find_mut(id))
+    }
+
+    pub fn toggle_layer_hidden(&mut self, id: Id) {
+        if let Some(layer) = self.find_layer_mut(id) {
+            layer.hidden = !layer.hidden;
+            layer.on_deselect();
+        }
+    }
+
+    pub fn toggle_layer_locked(&mut self, id: Id) {
+        if let Some(layer) = self.find_layer_mut(id) {
+            layer.locked = !layer.locked;
+        }
+    }
+
+    /// Replaces the selected layers with a single group holding them, placed where the
+    /// topmost of them was.
+    pub fn group_selection(&mut self) {
+        let selected = self.selected_indices();
+        let Some(&last) = selected.last() else {
+            return;
+        };
+
+        let mut children = Vec::with_capacity(selected.len());
+        for index in selected.iter().rev() {
+            let mut child = self.layers.remove(*index);
+            child.on_deselect();
+            children.push(child);
+        }
+        children.reverse();
+
+        self.groups_created += 1;
+        let mut group = Layer::new(
+            self.id_generator.generate(),
+            format!("Group {}", self.groups_created),
+            Box::new(GroupLayer::new(children)),
+        );
+        group.on_select();
+        self.layers.insert(last + 1 - selected.len(), group);
+    }
+
+    pub fn ungroup_selection(&mut self) {
+        let groups: Vec<Id> = self
+            .layers
+            .iter()
+            .filter(|layer| layer.is_selected && layer.is_group())
+            .map(|layer| layer.id)
+            .collect();
+
+        for id in groups {
+            self.ungroup(id);
+        }
+    }
+
+    /// Puts the children of a group back in its place, nested groups ungroup into their
+    /// parent group.
+    pub fn ungroup(&mut self, id: Id) {
+        if let Some(index) = self
+            .layers
+            .iter()
+            .position(|layer| layer.id == id && layer.is_group())
+        {
+            let mut group = self.layers.remove(index);
+            let mut children = group
+                .handler
+                .children_mut()
+                .map(std::mem::take)
+                .unwrap_or_default();
+            for child in &mut children {
+                child.on_select();
+            }
+            self.layers.splice(index..index, children);
+            return;
+        }
+
+        for layer in &mut self.layers {
+            if let Some(children) = layer.handler.children_mut() {
+                if ungroup_nested(children, id) {
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn auto_layout(&mut self, options: &LayoutOptions) {
+        let targets = self.arrangement_targets();
+        let items: Vec<LayoutItem> = targets
+            .iter()
+            .map(|index| &self.layers[*index])
+            .map(|layer| LayoutItem {
+                size: layer.handler.get_native_size(),
+                importance: layer.importance,
+            })
+            .collect();
+        let placements = layout::arrange(options, &items, self.bounds());
+        let fit = if options.crops() {
+            Fit::Cover
+        } else {
+            Fit::Stretch
+        };
+        let decoration = if options.kind == LayoutKind::Scatter {
+            Decoration {
+                polaroid: options.scatter.polaroid,
+                shadow: options.scatter.shadow,
+            }
+        } else {
+            Decoration::default()
+        };
+
+        for (index, placement) in targets.into_iter().zip(placements) {
+            let layer = &mut self.layers[index];
+            layer.handler.set_rect(placement.rect);
+            layer.handler.set_rotation(placement.rotation);
+            layer.handler.set_fit(fit);
+            layer.handler.set_corner_radius(options.corner_radius);
+            layer.handler.set_decoration(decoration);
+        }
+    }
+
+    pub fn tidy_up(&mut self, gap: f32) {
+        let targets = self.arrangement_targets();
+        let edges: Vec<_> = targets
+            .iter()
+            .map(|index| self.layers[*index].edges())
+            .collect();
+
+        for (index, delta) in targets.into_iter().zip(arrange::tidy(&edges, gap)) {
+            self.layers[index].move_by(delta);
+        }
+    }
+
+    pub fn align_selection(&mut self, align: Align, target: AlignTarget) {
+        let targets = self.movable_indices();
+        if targets.is_empty() {
+            return;
+        }
+        let edges: Vec<_> = targets
+            .iter()
+            .map(|index| self.layers[*index].edges())
+            .collect();
+        let reference = match target {
+            AlignTarget::Canvas => Edges::of(&self.bounds()),
+            AlignTarget::Selection => match arrange::bounds_of(&edges) {
+                Some(bounds) => bounds,
+                None => return,
+            },
+        };
+
+        for (index, delta) in targets
+            .into_iter()
+            .zip(arrange::align(&edges, align, &reference))
+        {
+            self.layers[index].move_by(delta);
+        }
+    }
+
+    pub fn distribute_selection(&mut self, axis: Axis) {
+        // Two layers have no gap between them to even out.
+        let targets = self.movable_indices();
+        if targets.len() < 3 {
+            return;
+        }
+        let edges: Vec<_> = targets
+            .iter()
+            .map(|index| self.layers[*index].edges())
+            .collect();
+
+        for (index, delta) in targets.into_iter().zip(arrange::distribute(&edges, axis)) {
+            self.layers[index].move_by(delta);
+        }
+    }
+
+    /// Selected layers that are moved, resized and arranged, locked ones stay in place.
+    fn movable_indices(&self) -> Vec<usize> {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.is_selected && !layer.locked)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Layers that layouts and tidying act on: the selection, or every unlocked layer when
+    /// nothing is selected.
+    fn arrangement_targets(&self) -> Vec<usize> {
+        if self.layers.iter().any(|layer| layer.is_selected) {
+            return self.movable_indices();
+        }
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.is_selectable())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn set_layer_importance(&mut self, id: Id, importance: f32) {
+        if let Some(layer) = self.layers.iter_mut().find(|layer| layer.id == id) {
+            layer.importance = importance;
+        }
+    }
+
+    fn bounds(&self) -> Rectangle {
+        Rectangle {
+            x: 0.,
+            y: 0.,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.is_selected)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The selected layer when exactly one is selected.
+    pub fn selected_layer(&self) -> Option<&Layer> {
+        match self.selected_indices().as_slice() {
+            [index] => Some(&self.layers[*index]),
+            _ => None,
+        }
+    }
+
+    /// Bounding box of every selected layer.
+    pub fn selection_bounds(&self) -> Option<Rectangle> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.is_selected)
+            .map(|layer| layer.handler.get_rect())
+            .reduce(|bounds, rect| bounds.union(&rect))
+    }
+
+    /// Selects only the layer at `index`. Locked layers can be selected from the panel,
+    /// the canvas only offers unlocked ones.
     pub fn select_layer(&mut self, index: usize) {
-        if let Some(selected_layer) = self.selected_layer {
-            self.layers[selected_layer].on_deselect();
+        self.deselect_layers();
+        if !self.layers[index].hidden {
+            self.layers[index].on_select();
         }
+    }
+
+    pub fn toggle_layer_selection(&mut self, index: usize) {
+        let layer = &mut self.layers[index];
+        if layer.is_selected {
+            layer.on_deselect();
+        } else if !layer.hidden {
+            layer.on_select();
+        }
+    }
 
-        self.selected_layer = Some(index);
-        self.layers[index].on_select();
+    pub fn select_all(&mut self) {
+        for layer in &mut self.layers {
+            if !layer.hidden {
+                layer.on_select();
+            }
+        }
+    }
+
+    pub fn select_in_rect(&mut self, rect: Rectangle, additive: bool) {
+        if !additive {
+            self.deselect_layers();
+        }
+
+        for layer in &mut self.layers {
+            if layer.is_selectable() && layer.intersects(&rect) {
+                layer.on_select();
+            }
+        }
+    }
+
+    /// Whether clicks should toggle layers in and out of the selection.
+    pub fn toggle_modifier_held(&self) -> bool {
+        self.shift_held || self.ctrl_held
     }
 
     pub fn on_start_drag(&mut self) {
@@ -99,9 +800,14 @@ impl MakerCanvas {
     }
 
     pub fn move_selection(&mut self, delta: Point, snap: bool) {
-        let Some(selected_layer) = self.selected_layer else {
-            return;
-        };
+        match self.movable_indices().as_slice() {
+            [] => {}
+            [selected_layer] => self.move_layer(*selected_layer, delta, snap),
+            selected => self.move_group(selected, delta, snap),
+        }
+    }
+
+    fn move_layer(&mut self, selected_layer: usize, delta: Point, snap: bool) {
         let mut bank = self.ignored_delta_bank;
         let mut delta = delta;
 
@@ -131,7 +837,10 @@ impl MakerCanvas {
 
             let (before, rest) = self.layers.split_at_mut(selected_layer);
             let (current_layer, after) = rest.split_first_mut().unwrap();
-            let other_layers = before.iter().chain(after.iter());
+            let other_layers = before
+                .iter()
+                .chain(after.iter())
+                .filter(|layer| !layer.hidden);
 
             let (ignored_delta, snap_point) =
                 current_layer.move_by_snap(delta, &other_layers.collect::<Vec<_>>(), &bounds);
@@ -148,11 +857,96 @@ impl MakerCanvas {
         }
     }
 
+    fn move_group(&mut self, selected: &[usize], delta: Point, snap: bool) {
+        let Some(group) = self.selection_bounds() else {
+            return;
+        };
+        let mut bank = self.ignored_delta_bank;
+        let mut delta = delta;
+
+        if !snap {
+            delta.x += bank.x;
+            delta.y += bank.y;
+            for index in selected {
+                self.layers[*index].move_by(delta);
+            }
+            self.ignored_delta_bank = Point::ORIGIN;
+            return;
+        }
+
+        const IGNORED_DELTA_THRESHOLD: f32 = 5.;
+
+        if (bank.x.abs() > IGNORED_DELTA_THRESHOLD) || (bank.y.abs() > IGNORED_DELTA_THRESHOLD) {
+            delta.x += bank.x;
+            delta.y += bank.y;
+            bank = Point::ORIGIN;
+        }
+
+        let other_layers: Vec<&Layer> = self
+            .layers
+            .iter()
+            .filter(|layer| !layer.is_selected && !layer.hidden)
+            .collect();
+        let (rect, ignored_delta, snap_point) =
+            layer::snap_move(group, delta, &other_layers, &self.bounds());
+        let mut applied = Point::new(rect.x - group.x, rect.y - group.y);
+
+        if snap_point.x < 0. && snap_point.y < 0. {
+            applied.x += bank.x;
+            applied.y += bank.y;
+            bank = Point::ORIGIN;
+        }
+
+        for index in selected {
+            self.layers[*index].move_by(applied);
+        }
+
+        self.snap_point = snap_point;
+        self.ignored_delta_bank = Point::new(bank.x + ignored_delta.x, bank.y + ignored_delta.y);
+    }
+
     pub fn resize_selection(&mut self, delta: Point, pivot: Point, preserve_aspect: bool) {
-        let Some(selected_layer) = self.selected_layer else {
+        match self.movable_indices().as_slice() {
+            [] => {}
+            [selected_layer] => self.resize_layer(*selected_layer, delta, pivot, preserve_aspect),
+            selected => self.resize_group(selected, delta, pivot, preserve_aspect),
+        }
+    }
+
+    /// Scales every selected layer with the selection bounding box.
+    fn resize_group(
+        &mut self,
+        selected: &[usize],
+        delta: Point,
+        pivot: Point,
+        preserve_aspect: bool,
+    ) {
+        let Some(group) = self.selection_bounds() else {
             return;
         };
+        let resized = layer::resized_rect(group, delta, pivot, preserve_aspect);
+        let scale_x = resized.width / group.width;
+        let scale_y = resized.height / group.height;
+
+        for index in selected {
+            let handler = &mut self.layers[*index].handler;
+            let rect = handler.get_rect();
+            handler.set_rect(Rectangle {
+                x: resized.x + (rect.x - group.x) * scale_x,
+                y: resized.y + (rect.y - group.y) * scale_y,
+                width: rect.width * scale_x,
+                height: rect.height * scale_y,
+            });
+        }
+    }
 
+    fn resize_layer(
+        &mut self,
+        selected_layer: usize,
+        delta: Point,
+        pivot: Point,
+        preserve_aspect: bool,
+    ) {
         let snap = true;
 
         if !snap {
@@ -181,7 +975,10 @@ impl MakerCanvas {
 
             let (before, rest) = self.layers.split_at_mut(selected_layer);
             let (current_layer, after) = rest.split_first_mut().unwrap();
-            let other_layers = before.iter().chain(after.iter());
+            let other_layers = before
+                .iter()
+                .chain(after.iter())
+                .filter(|layer| !layer.hidden);
 
             let (ignored_delta, snap_point) = current_layer.resize_by_snap(
                 delta,
@@ -204,51 +1001,101 @@ impl MakerCanvas {
     }
 
     pub fn deselect_layers(&mut self) {
-        if let Some(selected_layer) = self.selected_layer {
-            self.layers[selected_layer].on_deselect();
+        for layer in &mut self.layers {
+            layer.on_deselect();
         }
+    }
 
-        self.selected_layer = None;
+    /// Files to decode at full resolution before an export to `path`, fails while images
+    /// are still loading or missing.
+    pub fn export_loads(&self, path: &FilePath) -> Result<Vec<(Id, LoadRequest)>, Error> {
+        if any_loading(&self.layers) {
+            return Err(Error::export(
+                path,
+                "some images are still loading or missing",
+            ));
+        }
+
+        let mut loads = Vec::new();
+        collect_full_resolution_loads(&self.layers, &mut loads);
+        Ok(loads)
     }
 
-    pub fn export_as_png(&self, simulator: &mut Simulator, path: &PathBuf) {
-        let scale_factor = 2.0;
-        let screenshot = simulator
-            .screenshot(
-                self.view(),
-                Size {
-                    width: self.width,
-                    height: self.height,
-                },
-                scale_factor,
-            )
-            .unwrap();
+    /// Renders the canvas with the full resolution `pixels` decoded for
+    /// [`MakerCanvas::export_loads`] and saves it to `path`.
+    pub fn export_as_png(
+        &mut self,
+        simulator: &mut Simulator,
+        path: &PathBuf,
+        pixels: &ExportPixels,
+    ) -> Result<(), Error> {
+        // Layers edited while their pixels were decoded would be exported as they were.
+        let expected: Vec<(Id, u64)> = self
+            .export_loads(path)?
+            .iter()
+            .map(|(id, request)| (*id, request.generation))
+            .collect();
+        let decoded: Vec<(Id, u64)> = pixels
+            .0
+            .iter()
+            .map(|(id, generation, _)| (*id, *generation))
+            .collect();
+        if expected != decoded {
+            return Err(Error::export(
+                path,
+                "images changed while they were decoded",
+            ));
+        }
 
-        let _ = image::save_buffer(
-            &path,
+        for (id, _, full_resolution) in &pixels.0 {
+            if let Some(layer) = self.find_layer_mut(*id) {
+                layer
+                    .handler
+                    .set_full_resolution(Some(full_resolution.clone()));
+            }
+        }
+        let screenshot = simulator.screenshot(
+            self.view(),
+            Size {
+                width: self.width,
+                height: self.height,
+            },
+            EXPORT_SCALE_FACTOR,
+        );
+        for (id, _, _) in &pixels.0 {
+            if let Some(layer) = self.find_layer_mut(*id) {
+                layer.handler.set_full_resolution(None);
+            }
+        }
+        let screenshot = screenshot.map_err(|error| Error::export(path, error))?;
+
+        image::save_buffer(
+            path,
             &screenshot.bytes,
             screenshot.size.width,
             screenshot.size.height,
             image::ColorType::Rgba8,
         )
-        .map(|_| path)
-        .map_err(|error| PngError(error.to_string()));
+        .map_err(|error| Error::export(path, error))
     }
 
     pub fn set_shift_state(&mut self, held: bool) {
         self.shift_held = held;
 
-        if let Some(selected_layer) = self.selected_layer {
-            let bank = self.ignored_delta_bank;
+        let bank = self.ignored_delta_bank;
 
-            if bank.x.abs() > 0. || bank.y.abs() > 0. {
-                let layer = &mut self.layers[selected_layer];
-                layer.move_by(bank);
-                self.ignored_delta_bank = Point::ORIGIN;
+        if bank.x.abs() > 0. || bank.y.abs() > 0. {
+            for index in self.selected_indices() {
+                self.layers[index].move_by(bank);
             }
+            self.ignored_delta_bank = Point::ORIGIN;
         }
     }
 
+    pub fn set_ctrl_state(&mut self, held: bool) {
+        self.ctrl_held = held;
+    }
+
     pub fn on_left_button_released(&mut self) {
         self.ignored_delta_bank = Point::ORIGIN;
         self.snap_point = Point::new(-1., -1.);
@@ -257,8 +1104,23 @@ impl MakerCanvas {
 
 pub enum Interaction {
     None,
-    Dragging { position: Point },
-    Resizing { position: Point, pivot: Point },
+    Dragging {
+        position: Point,
+    },
+    Resizing {
+        position: Point,
+        pivot: Point,
+    },
+    Selecting {
+        start: Point,
+        end: Point,
+    },
+    /// Files are dragged over the window from the file manager, counting how many are
+    /// hovered and how many of them were dropped so far.
+    HoveringFiles {
+        hovered: usize,
+        dropped: usize,
+    },
 }
 
 impl Default for Interaction {
@@ -276,20 +1138,74 @@ impl canvas::Program<Message> for MakerCanvas {
         renderer: &Renderer,
         _theme: &Theme,
         bounds: iced::Rectangle,
-        _cursor: mouse::Cursor,
+        cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = Frame::new(renderer, bounds.size());
         let background = Path::rectangle(Point::ORIGIN, frame.size());
         frame.with_clip(Rectangle::with_size(bounds.size()), |mut clipping_frame| {
             clipping_frame.fill(&background, Color::from_rgb8(24, 24, 28));
             for layer in &self.layers {
-                layer.draw(&mut clipping_frame);
+                layer.draw(&mut clipping_frame, 1.);
+            }
+
+            if let Interaction::Selecting { start, end } = *state {
+                let marquee = Path::rectangle(
+                    Point::new(start.x.min(end.x), start.y.min(end.y)),
+                    Size::new((end.x - start.x).abs(), (end.y - start.y).abs()),
+                );
+                clipping_frame.fill(&marquee, Color::from_rgba8(0, 208, 255, 0.15));
+                clipping_frame.stroke(
+                    &marquee,
+                    Stroke {
+                        style: Style::Solid(Color::from_rgb8(0, 208, 255)),
+                        width: 1.0,
+                        ..Default::default()
+                    },
+                );
+            }
+
+            if let Interaction::HoveringFiles { hovered: 1, .. } = *state {
+                let target = cursor
+                    .position_in(bounds)
+                    .and_then(|position| self.image_layer_at(position));
+                if let Some(target) = target {
+                    let rect = target.handler.get_rect();
+                    clipping_frame.stroke(
+                        &Path::rectangle(rect.position(), rect.size()),
+                        Stroke {
+                            style: Style::Solid(Color::from_rgb8(0, 208, 255)),
+                            width: 3.0,
+                            line_dash: LineDash {
+                                segments: &[6., 4.],
+                                offset: 0,
+                            },
+                            ..Default::default()
+                        },
+                    );
+                }
             }
 
-            if let Some(selected_layer) = self.selected_layer {
+            if self.selected_indices().len() > 1 {
+                if let Some(group) = self.selection_bounds() {
+                    clipping_frame.stroke(
+                        &Path::rectangle(group.position(), group.size()),
+                        Stroke {
+                            style: Style::Solid(Color::from_rgb(1., 0., 0.)),
+                            width: 1.0,
+                            line_dash: LineDash {
+                                segments: &[6., 4.],
+                                offset: 0,
+                            },
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+
+            if let Some(selection_bounds) = self.selection_bounds() {
                 match *state {
                     Interaction::Dragging { .. } | Interaction::Resizing { .. } => {
-                        let rect = &self.layers[selected_layer].handler.get_rect();
+                        let rect = &selection_bounds;
                         let mut draw_line = |from: Point, to: Point| {
                             clipping_frame.stroke(
                                 &Path::line(from, to),
@@ -336,6 +1252,70 @@ impl canvas::Program<Message> for MakerCanvas {
         cursor: mouse::Cursor,
     ) -> Option<canvas::Action<Message>> {
         match event {
+            canvas::Event::Window(window::Event::FileHovered(_)) => {
+                *state = match *state {
+                    Interaction::HoveringFiles { hovered, .. } => Interaction::HoveringFiles {
+                        hovered: hovered + 1,
+                        dropped: 0,
+                    },
+                    _ => Interaction::HoveringFiles {
+                        hovered: 1,
+                        dropped: 0,
+                    },
+                };
+                return Some(canvas::Action::request_redraw());
+            }
+            canvas::Event::Window(window::Event::FilesHoveredLeft) => {
+                *state = Interaction::None;
+                return Some(canvas::Action::request_redraw());
+            }
+            canvas::Event::Window(window::Event::FileDropped(path)) => {
+                // Not every platform reports hovering, a drop without it counts as one file.
+                let (hovered, dropped) = match *state {
+                    Interaction::HoveringFiles { hovered, dropped } => (hovered.max(1), dropped),
+                    _ => (1, 0),
+                };
+                *state = if dropped + 1 < hovered {
+                    Interaction::HoveringFiles {
+                        hovered,
+                        dropped: dropped + 1,
+                    }
+                } else {
+                    Interaction::None
+                };
+
+                let position = cursor.position_in(bounds);
+
+                // Dropping a single image onto another swaps its picture, keeping its rect
+                // and settings.
+                if hovered == 1 && path.is_file() {
+                    if let Some(layer) = position.and_then(|position| self.image_layer_at(position))
+                    {
+                        return Some(canvas::Action::publish(Message::ReplacementSelected(
+                            layer.id,
+                            Some(path.clone()),
+                        )));
+                    }
+                }
+
+                return Some(canvas::Action::publish(Message::FileDropped(
+                    path.clone(),
+                    position,
+                    dropped,
+                )));
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                let position = cursor.position_in(bounds)?;
+
+                // Locked layers still get a menu so they can be unlocked from it.
+                let layer = self
+                    .layers
+                    .iter()
+                    .rev()
+                    .find(|layer| !layer.hidden && layer.contains(position))?;
+
+                return Some(canvas::Action::publish(Message::OpenContextMenu(layer.id)));
+            }
             canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
                 let Some(position) = cursor.position() else {
                     return None;
@@ -346,30 +1326,46 @@ impl canvas::Program<Message> for MakerCanvas {
                     y: position.y - bounds.y,
                 };
 
-                if let Some(selected_layer) = self.selected_layer {
-                    let layer_rect = self.layers[selected_layer].handler.get_rect();
-                    if let Some(pivot) = position_to_pivot(in_cursor_position, &layer_rect, 4.) {
+                if let Some(selection_bounds) = self.selection_bounds() {
+                    if let Some(pivot) =
+                        position_to_pivot(in_cursor_position, &selection_bounds, 4.)
+                    {
                         *state = Interaction::Resizing { position, pivot };
                         return Some(canvas::Action::publish(Message::StartDrag));
                     }
                 }
 
                 for (index, layer) in self.layers.iter().enumerate().rev() {
-                    let rect = layer.handler.get_rect();
-                    if !rect.contains(in_cursor_position) {
+                    if !layer.is_selectable() {
                         continue;
                     }
 
-                    if let Some(selected_layer) = self.selected_layer {
-                        if selected_layer == index {
-                            *state = Interaction::Dragging { position };
-                            return Some(canvas::Action::publish(Message::StartDrag));
-                        }
+                    if !layer.contains(in_cursor_position) {
+                        continue;
+                    }
+
+                    if self.toggle_modifier_held() {
+                        return Some(canvas::Action::publish(Message::ToggleLayerSelection(
+                            index,
+                        )));
+                    }
+
+                    if layer.is_selected {
+                        *state = Interaction::Dragging { position };
+                        return Some(canvas::Action::publish(Message::StartDrag));
                     }
 
                     return Some(canvas::Action::publish(Message::SelectLayer(index)));
                 }
 
+                *state = Interaction::Selecting {
+                    start: in_cursor_position,
+                    end: in_cursor_position,
+                };
+
+                if self.toggle_modifier_held() {
+                    return Some(canvas::Action::request_redraw());
+                }
                 return Some(canvas::Action::publish(Message::DeselectLayers));
             }
 
@@ -398,11 +1394,45 @@ impl canvas::Program<Message> for MakerCanvas {
                         preserve_aspect,
                     )));
                 }
+                Interaction::Selecting { start, .. } => {
+                    let end = Point {
+                        x: position.x - bounds.x,
+                        y: position.y - bounds.y,
+                    };
+                    *state = Interaction::Selecting { start, end };
+                    return Some(canvas::Action::request_redraw());
+                }
+                Interaction::HoveringFiles { .. } => {
+                    return Some(canvas::Action::request_redraw());
+                }
                 Interaction::None => (),
             },
             canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
-                if let Interaction::None = *state {
-                    return None;
+                if let Some(path) = &self.dragged_asset {
+                    let position = cursor.position_in(bounds)?;
+                    return Some(canvas::Action::publish(Message::FileDropped(
+                        path.clone(),
+                        Some(position),
+                        0,
+                    )));
+                }
+
+                match *state {
+                    Interaction::None => return None,
+                    Interaction::Selecting { start, end } => {
+                        *state = Interaction::None;
+                        let marquee = Rectangle {
+                            x: start.x.min(end.x),
+                            y: start.y.min(end.y),
+                            width: (end.x - start.x).abs(),
+                            height: (end.y - start.y).abs(),
+                        };
+                        return Some(canvas::Action::publish(Message::SelectInRect(
+                            marquee,
+                            self.toggle_modifier_held(),
+                        )));
+                    }
+                    _ => (),
                 }
 
                 *state = Interaction::None;
@@ -424,8 +1454,10 @@ impl canvas::Program<Message> for MakerCanvas {
             Interaction::Resizing { position, pivot } => {
                 return pivot_to_cursor(position, &bounds, Some(pivot));
             }
+            Interaction::Selecting { .. } => return mouse::Interaction::Crosshair,
+            Interaction::HoveringFiles { .. } => return mouse::Interaction::Copy,
             Interaction::None => {
-                if let Some(selected_layer) = self.selected_layer {
+                if let Some(layer_rect) = self.selection_bounds() {
                     let cursor_position = match cursor.position() {
                         Some(pos) => Point { x: pos.x, y: pos.y },
                         None => return mouse::Interaction::default(),
@@ -436,7 +1468,6 @@ impl canvas::Program<Message> for MakerCanvas {
                         y: cursor_position.y - bounds.y,
                     };
 
-                    let layer_rect = self.layers[selected_layer].handler.get_rect();
                     let opt_pivot = position_to_pivot(in_cursor_position, &layer_rect, 4.);
                     return pivot_to_cursor(in_cursor_position, &layer_rect, opt_pivot);
                 }
@@ -446,6 +1477,143 @@ impl canvas::Program<Message> for MakerCanvas {
     }
 }
 
+fn remove_nested(layers: &mut Vec<Layer>, id: Id) -> bool {
+    if let Some(index) = layers.iter().position(|layer| layer.id == id) {
+        layers.remove(index);
+        return true;
+    }
+
+    layers.iter_mut().any(|layer| {
+        layer
+            .handler
+            .children_mut()
+            .is_some_and(|children| remove_nested(children, id))
+    })
+}
+
+fn collect_pending_loads(layers: &mut [Layer], loads: &mut Vec<(Id, LoadRequest)>) {
+    for layer in layers {
+        if let Some(request) = layer.handler.take_pending_load() {
+            loads.push((layer.id, request));
+        }
+        if let Some(children) = layer.handler.children_mut() {
+            collect_pending_loads(children, loads);
+        }
+    }
+}
+
+fn collect_full_resolution_loads(layers: &[Layer], loads: &mut Vec<(Id, LoadRequest)>) {
+    for layer in layers {
+        if let Some(request) = layer.handler.full_resolution_load() {
+            loads.push((layer.id, request));
+        }
+        if let Some(children) = layer.handler.children() {
+            collect_full_resolution_loads(children, loads);
+        }
+    }
+}
+
+/// Decodes the files of an export to `path` at full resolution.
+///
+/// This reads every file, call it off the update thread.
+pub fn decode_for_export(
+    path: &FilePath,
+    loads: &[(Id, LoadRequest)],
+) -> Result<ExportPixels, Error> {
+    loads
+        .iter()
+        .map(|(id, request)| {
+            decode::full_resolution(request)
+                .map(|image| (*id, request.generation, Arc::new(image.into_rgba8())))
+                .map_err(|error| Error::export(path, error))
+        })
+        .collect::<Result<_, _>>()
+        .map(ExportPixels)
+}
+
+/// Name a layer gets from its file.
+fn layer_name(path: &FilePath) -> String {
+    path.file_name()
+        .and_then(|os_str| os_str.to_str())
+        .unwrap_or("default_name")
+        .to_string()
+}
+
+fn refresh_nested(layers: &mut [Layer], path: &FilePath) {
+    for layer in layers {
+        if layer
+            .handler
+            .source_path()
+            .is_some_and(|source| source.starts_with(path))
+        {
+            layer.handler.refresh_source();
+        }
+        if let Some(children) = layer.handler.children_mut() {
+            refresh_nested(children, path);
+        }
+    }
+}
+
+fn collect_source_folders(layers: &[Layer], folders: &mut Vec<PathBuf>) {
+    for layer in layers {
+        if let Some(folder) = layer.handler.source_path().and_then(|path| path.parent()) {
+            folders.push(folder.to_path_buf());
+        }
+        if let Some(children) = layer.handler.children() {
+            collect_source_folders(children, folders);
+        }
+    }
+}
+
+fn collect_missing(layers: &[Layer], missing: &mut Vec<(Id, String, PathBuf)>) {
+    for layer in layers {
+        if let Some(path) = layer
+            .handler
+            .source_path()
+            .filter(|_| layer.handler.is_missing())
+        {
+            missing.push((layer.id, layer.name.clone(), path.clone()));
+        }
+        if let Some(children) = layer.handler.children() {
+            collect_missing(children, missing);
+        }
+    }
+}
+
+fn any_loading(layers: &[Layer]) -> bool {
+    layers.iter().any(|layer| {
+        layer.handler.is_loading()
+            || layer.handler.is_missing()
+            || layer
+                .handler
+                .children()
+                .is_some_and(|children| any_loading(children))
+    })
+}
+
+fn ungroup_nested(layers: &mut Vec<Layer>, id: Id) -> bool {
+    if let Some(index) = layers
+        .iter()
+        .position(|layer| layer.id == id && layer.is_group())
+    {
+        let mut group = layers.remove(index);
+        let children = group
+            .handler
+            .children_mut()
+            .map(std::mem::take)
+            .unwrap_or_default();
+        layers.splice(index..index, children);
+        return true;
+    }
+
+    layers.iter_mut().any(|layer| {
+        layer
+            .handler
+            .children_mut()
+            .is_some_and(|children| ungroup_nested(children, id))
+    })
+}
+
 fn position_to_pivot(cursor_position: Point, bounds: &Rectangle, threshold: f32) -> Option<Point> {
     let mut pivot_x = -1.0;
     let mut pivot_y = -1.0;
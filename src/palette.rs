@@ -0,0 +1,179 @@
+use iced::Size;
+
+use crate::{
+    arrange::{Align, Axis},
+    bg_maker::Message,
+    keymap::Action,
+    layout::LayoutKind,
+};
+
+/// Canvas sizes offered by the palette, named after common screen resolutions.
+const CANVAS_PRESETS: [(&str, f32, f32); 6] = [
+    ("HD", 1280., 720.),
+    ("Full HD", 1920., 1080.),
+    ("QHD", 2560., 1440.),
+    ("4K UHD", 3840., 2160.),
+    ("Ultrawide", 3440., 1440.),
+    ("Phone", 1080., 2340.),
+];
+
+/// Entry of the command palette.
+pub struct Command {
+    pub title: String,
+    pub message: Message,
+    /// Action whose key bindings are shown next to the title.
+    pub action: Option<Action>,
+}
+
+impl Command {
+    fn new(title: impl Into<String>, message: Message) -> Self {
+        Self {
+            title: title.into(),
+            message,
+            action: None,
+        }
+    }
+}
+
+/// Every operation that can be run from the palette.
+pub fn commands() -> Vec<Command> {
+    let mut commands = vec![
+        Command::new("Add images", Message::AddImage),
+        Command::new("Add folder", Message::OpenImportDialog),
+        Command::new("Toggle asset browser", Message::ToggleAssetBrowser),
+        Command::new("Add library folder", Message::AddLibraryFolder),
+        Command::new("Relink missing images", Message::OpenRelinkDialog),
+        Command::new("Export to PNG", Message::SaveAsPng),
+        Command::new("Save & apply as wallpaper", Message::SaveApply),
+        Command::new("Auto layout", Message::AutoLayout),
+        Command::new("Shuffle layout", Message::ShuffleLayout),
+        Command::new("Tidy up", Message::TidyUp),
+        Command::new(
+            "Distribute horizontally",
+            Message::DistributeSelection(Axis::Horizontal),
+        ),
+        Command::new(
+            "Distribute vertically",
+            Message::DistributeSelection(Axis::Vertical),
+        ),
+        Command::new("Edit keyboard shortcuts", Message::OpenShortcutSettings),
+    ];
+
+    commands.extend(
+        Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::CommandPalette)
+            .map(|action| Command {
+                title: action.to_string(),
+                message: action.message(),
+                action: Some(action),
+            }),
+    );
+
+    commands.extend(
+        Align::ALL
+            .into_iter()
+            .map(|align| Command::new(format!("Align {align}"), Message::AlignSelection(align))),
+    );
+
+    commands.extend(LayoutKind::ALL.into_iter().map(|kind| {
+        Command::new(
+            format!("Layout style: {kind}"),
+            Message::LayoutKindSelected(kind),
+        )
+    }));
+
+    commands.extend(CANVAS_PRESETS.into_iter().map(|(name, width, height)| {
+        Command::new(
+            format!("Canvas size: {name} ({width} × {height})"),
+            Message::CanvasSizeSelected(Size::new(width, height)),
+        )
+    }));
+
+    commands
+}
+
+/// Most results the palette shows at once.
+const MAX_RESULTS: usize = 12;
+
+/// Commands matching `query`, best match first, at most [`MAX_RESULTS`] of them.
+pub fn search<'a>(commands: &'a [Command], query: &str) -> Vec<&'a Command> {
+    let mut matches: Vec<(i32, &Command)> = commands
+        .iter()
+        .filter_map(|command| fuzzy_score(query, &command.title).map(|score| (score, command)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+
+    matches
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, command)| command)
+        .collect()
+}
+
+/// Scores `text` when every character of `query` appears in it in order, rewarding
+/// consecutive characters and matches at the start of words.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for character in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|c| *c == character)?;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position).min(5) as i32;
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_query_character_has_to_match_in_order() {
+        assert!(fuzzy_score("sall", "Select all").is_some());
+        assert!(fuzzy_score("lls", "Select all").is_none());
+        assert!(fuzzy_score("xyz", "Select all").is_none());
+        assert_eq!(fuzzy_score("", "Select all"), Some(0));
+    }
+
+    #[test]
+    fn scores_ignore_case_and_spaces() {
+        assert_eq!(
+            fuzzy_score("SEL ALL", "Select all"),
+            fuzzy_score("selall", "Select all")
+        );
+    }
+
+    #[test]
+    fn word_starts_and_runs_score_higher() {
+        assert!(fuzzy_score("sel", "Select all") > fuzzy_score("sel", "Paste layers"));
+        assert!(fuzzy_score("sa", "Select all") > fuzzy_score("sa", "Paste layers"));
+    }
+
+    #[test]
+    fn search_puts_the_best_match_first() {
+        let commands = commands();
+
+        let results = search(&commands, "dup");
+        assert_eq!(
+            results.first().map(|command| command.title.as_str()),
+            Some("Duplicate")
+        );
+        assert_eq!(search(&commands, "").len(), MAX_RESULTS);
+        assert!(search(&commands, "zzz").is_empty());
+    }
+}
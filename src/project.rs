@@ -0,0 +1,256 @@
+use std::{fs, io, path::Path, path::PathBuf};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use iced::{Rectangle, Size};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    layer_handler::{Decoration, Fit},
+};
+
+/// Marks clipboard text that holds layers copied from a canvas.
+const CLIPBOARD_PREFIX: &str = "bg-maker-layers:";
+
+/// Extension of saved project files.
+pub const PROJECT_EXTENSION: &str = "bgmaker";
+
+/// Everything needed to reopen a canvas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectData {
+    pub width: f32,
+    pub height: f32,
+    pub layers: Vec<LayerData>,
+    /// Seed of the random layouts, so a scatter or mosaic can be reproduced.
+    #[serde(default)]
+    pub layout_seed: u64,
+}
+
+impl ProjectData {
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+/// Serializable copy of a layer and everything nested in it, without its id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerData {
+    pub name: String,
+    pub importance: f32,
+    pub hidden: bool,
+    pub locked: bool,
+    pub opacity: f32,
+    pub expanded: bool,
+    pub content: ContentData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ContentData {
+    Image {
+        source: SourceData,
+        rect: RectData,
+        fit: Fit,
+        corner_radius: f32,
+        rotation: f32,
+        decoration: Decoration,
+        flip_horizontal: bool,
+        flip_vertical: bool,
+        /// Frame shown from an animated GIF or APNG.
+        #[serde(default)]
+        frame: usize,
+    },
+    Group {
+        children: Vec<LayerData>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SourceData {
+    File(PathBuf),
+    /// Base64 PNG of an image that has no file, so it survives being saved or copied.
+    Embedded(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RectData {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl From<Rectangle> for RectData {
+    fn from(rect: Rectangle) -> Self {
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+impl From<RectData> for Rectangle {
+    fn from(rect: RectData) -> Self {
+        Rectangle {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+/// Encodes the pixels of a pasted image so they can be saved or copied without a file.
+pub fn embed_pixels(pixels: &image::RgbaImage) -> Result<SourceData, Error> {
+    let mut png = io::Cursor::new(Vec::new());
+    pixels
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|error| Error::EmbeddedImage {
+            name: "Pasted image".to_string(),
+            reason: error.to_string(),
+        })?;
+
+    Ok(SourceData::Embedded(STANDARD.encode(png.into_inner())))
+}
+
+pub fn decode_embedded(data: &str) -> Result<image::RgbaImage, String> {
+    let png = STANDARD.decode(data).map_err(|error| error.to_string())?;
+    image::load_from_memory(&png)
+        .map(|image| image.into_rgba8())
+        .map_err(|error| error.to_string())
+}
+
+pub fn save(path: &Path, project: &ProjectData) -> io::Result<()> {
+    let json = serde_json::to_string(project).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+pub fn load(path: &Path) -> io::Result<ProjectData> {
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(io::Error::other)
+}
+
+pub fn to_clipboard(layers: &[LayerData]) -> Option<String> {
+    serde_json::to_string(layers)
+        .ok()
+        .map(|json| format!("{CLIPBOARD_PREFIX}{json}"))
+}
+
+/// Reads layers back from clipboard text, `None` when the text isn't a copy of layers.
+pub fn from_clipboard(contents: &str) -> Option<Vec<LayerData>> {
+    let json = contents.strip_prefix(CLIPBOARD_PREFIX)?;
+    serde_json::from_str(json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image_layer(name: &str, source: SourceData) -> LayerData {
+        LayerData {
+            name: name.to_string(),
+            importance: 2.5,
+            hidden: false,
+            locked: true,
+            opacity: 0.75,
+            expanded: true,
+            content: ContentData::Image {
+                source,
+                rect: RectData {
+                    x: 12.5,
+                    y: -4.,
+                    width: 640.,
+                    height: 360.,
+                },
+                fit: Fit::Cover,
+                corner_radius: 8.,
+                rotation: 0.1,
+                decoration: Decoration {
+                    polaroid: true,
+                    shadow: false,
+                },
+                flip_horizontal: true,
+                flip_vertical: false,
+                frame: 3,
+            },
+        }
+    }
+
+    fn project() -> ProjectData {
+        let pixels = image::RgbaImage::from_pixel(2, 1, image::Rgba([255, 0, 0, 128]));
+
+        ProjectData {
+            width: 1920.,
+            height: 1080.,
+            layers: vec![
+                image_layer(
+                    "photo.jpg",
+                    SourceData::File(PathBuf::from("photos/photo.jpg")),
+                ),
+                LayerData {
+                    name: "Group".to_string(),
+                    importance: 1.,
+                    hidden: true,
+                    locked: false,
+                    opacity: 1.,
+                    expanded: false,
+                    content: ContentData::Group {
+                        children: vec![image_layer("Pasted image", embed_pixels(&pixels).unwrap())],
+                    },
+                },
+            ],
+            layout_seed: 42,
+        }
+    }
+
+    /// Compares through JSON since the data types don't implement `PartialEq`.
+    fn json<T: Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn projects_survive_saving_and_loading() {
+        let path = std::env::temp_dir().join(format!(
+            "bg-maker-project-{}.{PROJECT_EXTENSION}",
+            std::process::id()
+        ));
+        let project = project();
+
+        save(&path, &project).unwrap();
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(json(&loaded), json(&project));
+    }
+
+    #[test]
+    fn embedded_pixels_decode_unchanged() {
+        let pixels =
+            image::RgbaImage::from_fn(3, 2, |x, y| image::Rgba([x as u8, y as u8, 7, 255]));
+        let SourceData::Embedded(data) = embed_pixels(&pixels).unwrap() else {
+            panic!("pixels were not embedded");
+        };
+
+        assert_eq!(decode_embedded(&data).unwrap(), pixels);
+        assert!(decode_embedded("not base64!").is_err());
+    }
+
+    #[test]
+    fn projects_without_a_seed_still_load() {
+        let project: ProjectData =
+            serde_json::from_str(r#"{"width":800.0,"height":600.0,"layers":[]}"#).unwrap();
+
+        assert_eq!(project.layout_seed, 0);
+        assert_eq!(project.size(), Size::new(800., 600.));
+    }
+
+    #[test]
+    fn clipboard_text_round_trips_layers_only() {
+        let layers = project().layers;
+        let text = to_clipboard(&layers).unwrap();
+
+        assert_eq!(json(&from_clipboard(&text).unwrap()), json(&layers));
+        assert!(from_clipboard("just some text").is_none());
+    }
+}
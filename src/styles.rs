@@ -1,4 +1,4 @@
-use iced::{widget::container::Style, Border, Theme};
+use iced::{widget::container::Style, Border, Color, Theme};
 
 pub fn bordered_box(theme: &Theme) -> Style {
     let palette = theme.extended_palette();
@@ -27,3 +27,24 @@ pub fn selected_bordered_box(theme: &Theme) -> Style {
         ..Style::default()
     }
 }
+
+pub fn backdrop(_theme: &Theme) -> Style {
+    Style {
+        background: Some(Color::from_rgba(0., 0., 0., 0.5).into()),
+        ..Style::default()
+    }
+}
+
+pub fn drop_target_box(theme: &Theme) -> Style {
+    let palette = theme.extended_palette();
+
+    Style {
+        background: Some(palette.primary.weak.color.into()),
+        border: Border {
+            width: 3.0,
+            radius: 0.0.into(),
+            color: palette.primary.strong.color,
+        },
+        ..Style::default()
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use image::{DynamicImage, RgbaImage};
+
+use crate::{decode, error::Error, utils};
+
+/// Longest side of a thumbnail, enough for the asset browser grid on high density screens.
+pub const THUMBNAIL_SIDE: u32 = 128;
+
+/// The cache is trimmed to this many bytes, least recently used thumbnails first.
+const MAX_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Scanning the cache folder is slow, so it is trimmed at most this often while
+/// thumbnails are stored.
+const EVICTION_INTERVAL: Duration = Duration::from_secs(30);
+
+static LAST_EVICTION: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Thumbnail of an image file, from the cache or decoded and cached when it is missing or
+/// the file changed since, along with why caching it failed.
+///
+/// Decodes the whole file on a miss, call it off the update thread.
+pub fn load(path: &Path) -> Result<(Arc<RgbaImage>, Option<Error>), Error> {
+    match cached(path) {
+        Some(thumbnail) => Ok((thumbnail, None)),
+        None => decode::preview(&decode::LoadRequest {
+            path: path.to_path_buf(),
+            frame: 0,
+            frame_count: None,
+            generation: 0,
+            raster_size: None,
+        })
+        .map(|preview| (preview.thumbnail, preview.cache_error)),
+    }
+}
+
+/// Cached thumbnail of `path` when one exists for its current size and modification time.
+pub fn cached(path: &Path) -> Option<Arc<RgbaImage>> {
+    let entry = entry_path(path)?;
+    let thumbnail = image::open(&entry).ok()?.into_rgba8();
+
+    // Reading counts as a use, so eviction keeps the thumbnails that are shown.
+    if let Ok(file) = File::options().write(true).open(&entry) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+
+    Some(Arc::new(thumbnail))
+}
+
+/// Scales `pixels` of `path` down to a thumbnail and caches it, the thumbnail is returned
+/// even when caching fails, along with the error.
+pub fn store(path: &Path, pixels: &RgbaImage) -> (Arc<RgbaImage>, Option<Error>) {
+    let thumbnail = DynamicImage::ImageRgba8(pixels.clone())
+        .thumbnail(THUMBNAIL_SIDE, THUMBNAIL_SIDE)
+        .into_rgba8();
+
+    let mut cache_error = None;
+    if let Some(entry) = entry_path(path) {
+        let saved = entry
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|error| error.to_string())
+            .and_then(|()| thumbnail.save(&entry).map_err(|error| error.to_string()));
+        match saved {
+            Ok(()) => evict_if_due(),
+            Err(reason) => {
+                cache_error = Some(Error::ThumbnailCache {
+                    path: path.to_path_buf(),
+                    reason,
+                })
+            }
+        }
+    }
+
+    (Arc::new(thumbnail), cache_error)
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    utils::cache_dir().map(|dir| dir.join("thumbnails"))
+}
+
+/// Cache file of `path`, named after its path, size and modification time so edited
+/// files get a new thumbnail.
+fn entry_path(path: &Path) -> Option<PathBuf> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?;
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+    let mut hash = Fnv::new();
+    hash.write(path.as_os_str().as_encoded_bytes());
+    hash.write(&metadata.len().to_le_bytes());
+    hash.write(&modified.as_nanos().to_le_bytes());
+
+    cache_dir().map(|dir| dir.join(format!("{:016x}.png", hash.0)))
+}
+
+/// Trims the cache unless that was done within [`EVICTION_INTERVAL`], so a folder full of
+/// new images doesn't rescan the cache for each of them.
+fn evict_if_due() {
+    {
+        let Ok(mut last_eviction) = LAST_EVICTION.lock() else {
+            return;
+        };
+        if last_eviction.is_some_and(|last| last.elapsed() < EVICTION_INTERVAL) {
+            return;
+        }
+        *last_eviction = Some(Instant::now());
+    }
+
+    evict();
+}
+
+/// Deletes the least recently used thumbnails until the cache fits [`MAX_CACHE_BYTES`].
+fn evict() {
+    let Some(entries) = cache_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return;
+    };
+
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            metadata
+                .is_file()
+                .then(|| (used, metadata.len(), entry.path()))
+        })
+        .collect();
+
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= MAX_CACHE_BYTES {
+        return;
+    }
+
+    files.sort_by_key(|(used, _, _)| *used);
+    for (_, size, path) in files {
+        if total <= MAX_CACHE_BYTES {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+}
+
+/// FNV-1a, a hash that stays the same across builds unlike the standard library's.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 #[cfg(windows)]
 pub mod wallpaper {
+    use std::{os::windows::ffi::OsStrExt, path::Path};
+
     use windows::{
         Win32::{
             System::Com::{
@@ -10,14 +12,24 @@ pub mod wallpaper {
         core::PCWSTR,
     };
 
-    pub fn set_wallpaper(image_path: &str) -> Result<(), windows::core::Error> {
+    use crate::error::Error;
+
+    pub fn set_wallpaper(image_path: &Path) -> Result<(), Error> {
+        apply(image_path).map_err(|error| Error::Wallpaper(error.to_string()))
+    }
+
+    fn apply(image_path: &Path) -> windows::core::Result<()> {
         unsafe {
             let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
 
             let desktop_wallpaper: IDesktopWallpaper =
                 CoCreateInstance(&DesktopWallpaper, None, CLSCTX_ALL)?;
 
-            let wide_image_path: Vec<u16> = image_path.encode_utf16().collect();
+            let wide_image_path: Vec<u16> = image_path
+                .as_os_str()
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect();
             let pcwstr_image_path = PCWSTR(wide_image_path.as_ptr());
 
             desktop_wallpaper.SetWallpaper(PCWSTR::null(), pcwstr_image_path)?;
@@ -31,7 +43,67 @@ pub mod wallpaper {
 
 #[cfg(unix)]
 pub mod wallpaper {
-    pub fn set_wallpaper(image_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        todo!();
+    use std::path::Path;
+
+    use crate::error::Error;
+
+    pub fn set_wallpaper(_image_path: &Path) -> Result<(), Error> {
+        Err(Error::Wallpaper(
+            "not supported on this platform yet".to_string(),
+        ))
     }
 }
+
+/// Directory for the app's settings files, following the platform conventions.
+pub fn config_dir() -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    base.map(|dir| dir.join("bg-maker"))
+}
+
+/// Directory for files the app can recreate, like thumbnails, following the platform
+/// conventions.
+pub fn cache_dir() -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    let base = if cfg!(windows) {
+        env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+
+    base.map(|dir| dir.join("bg-maker"))
+}
+
+/// Opens the system file manager with `path` highlighted where the platform supports it.
+pub fn reveal_in_file_manager(path: &std::path::Path) -> std::io::Result<()> {
+    use std::process::Command;
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let mut command = Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+
+    command.spawn().map(|_| ())
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use iced::futures::{SinkExt, Stream, StreamExt, channel::mpsc};
+use notify::{RecursiveMode, Watcher};
+
+use crate::bg_maker::Message;
+
+/// A changed file is reported once it had no events for this long, editors and copies
+/// write files in many steps.
+const QUIET_PERIOD: Duration = Duration::from_millis(300);
+
+/// Reports every file that changes, appears or disappears in `folders`.
+///
+/// Takes a `&Vec` because it is the builder of a `Subscription::run_with`.
+#[allow(clippy::ptr_arg)]
+pub fn watch(folders: &Vec<PathBuf>) -> impl Stream<Item = Message> + use<> {
+    let folders = folders.clone();
+
+    iced::stream::channel(16, move |mut output| async move {
+        let (events_sender, events) = std::sync::mpsc::channel();
+        let Ok(mut watcher) =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let _ = events_sender.send(event);
+            })
+        else {
+            return;
+        };
+
+        // Folders that can't be watched, like ones that were deleted, are skipped.
+        for folder in &folders {
+            let _ = watcher.watch(folder, RecursiveMode::NonRecursive);
+        }
+
+        // The thread stops once the watcher is dropped along with the subscription.
+        let (sender, mut changes) = mpsc::unbounded();
+        thread::spawn(move || debounce(&events, &sender));
+
+        while let Some(path) = changes.next().await {
+            if output.send(Message::SourceChanged(path)).await.is_err() {
+                return;
+            }
+        }
+    })
+}
+
+/// Forwards each path of `events` to `changes` after [`QUIET_PERIOD`] without new events
+/// for it.
+fn debounce(
+    events: &Receiver<notify::Result<notify::Event>>,
+    changes: &mpsc::UnboundedSender<PathBuf>,
+) {
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+
+    loop {
+        let timeout = pending.values().min().map_or(Duration::MAX, |last| {
+            QUIET_PERIOD.saturating_sub(last.elapsed())
+        });
+
+        match events.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                let now = Instant::now();
+                for path in event.paths {
+                    pending.insert(path, now);
+                }
+            }
+            Ok(Err(_)) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let quiet: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, last)| last.elapsed() >= QUIET_PERIOD)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in quiet {
+            pending.remove(&path);
+            if changes.unbounded_send(path).is_err() {
+                return;
+            }
+        }
+    }
+}